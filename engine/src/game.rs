@@ -0,0 +1,1006 @@
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
+use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::markov::MarkovChain;
+use crate::validity::{self, CheatFlag};
+
+/// how text is shown to the player
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    Normal,
+    Rewrite,
+}
+/// everything chosen before the game starts
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConf {
+    pub mode: GameMode,
+    pub limit: Limit,
+    pub text_source: TextSource,
+    pub strictness: Strictness,
+    ///language pack used for generated text
+    pub language: String,
+}
+impl GameConf {
+    pub fn new() -> GameConf {
+        GameConf {
+            mode: GameMode::Normal,
+            limit: Limit::Time(Duration::from_secs(30)),
+            text_source: TextSource::Generated,
+            strictness: Strictness::Normal,
+            language: "en".to_string(),
+        }
+    }
+}
+impl Default for GameConf {
+    fn default() -> Self {
+        GameConf::new()
+    }
+}
+
+/// where text for the game comes from
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum TextSource {
+    ///generated with markov chain
+    Generated,
+    ///text from file with given path
+    File(String),
+}
+
+/// how mistakes are treated
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Strictness {
+    ///wrong letter is written and game goes on
+    Normal,
+    ///wrong letter is not written, correct one has to be typed to move on
+    Strict,
+}
+
+///amount of generated words when there is no limit
+const WHOLE_TEXT_WORD_COUNT: u32 = 50;
+
+/// when the game ends, game always ends when whole text is typed
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Limit {
+    Time(Duration),
+    WordCount(u32),
+    None,
+}
+
+/// splits text into letters, accents and joined emoji stay in the letter they belong to
+pub fn graphemes(text: &str) -> Vec<String> {
+    text.nfc()
+        .collect::<String>()
+        .graphemes(true)
+        .map(|g| g.to_string())
+        .collect()
+}
+
+/// state of one game: text to type, typed letters and statistics
+#[derive(Clone, Debug)]
+pub struct Game {
+    ///text split into grapheme clusters
+    pub correct_text: Vec<String>,
+    pub written_vec: Vec<Letter>,
+    ///chars typed for the letter that needs more than one key, like "क्ष"
+    pub pending: String,
+    pub statistics: GameStat,
+    // pub game_mode: GameMode,
+    pub game_conf: GameConf,
+}
+impl Game {
+    /// Creates new game
+    ///
+    /// `text` is used to generate markov chain for generated text source,
+    /// for file text source it is the text to type
+    pub fn new(size: usize, conf: GameConf, text: Option<String>) -> Game {
+        let text = text.unwrap_or_default();
+        if let TextSource::File(_) = conf.text_source {
+            return Game::from_text(conf, &text);
+        }
+        Game::generated(size, conf, &MarkovChain::new(&text, &[]))
+    }
+
+    /// Creates new game with text generated by the chain,
+    /// `size` is amount of words for time limit
+    pub fn generated(size: usize, mut conf: GameConf, chain: &MarkovChain) -> Game {
+        let word_count = match conf.limit {
+            Limit::Time(t) => {
+                if t.as_secs() == 0 {
+                    conf.limit = Limit::Time(Duration::from_secs(1));//change 0 sec to 1 sec to avoid crashing on game creation
+                }
+                size as u32
+            }
+            Limit::WordCount(wc) => wc,
+            Limit::None => WHOLE_TEXT_WORD_COUNT,
+        };
+        Game {
+            correct_text: graphemes(&chain.generate(word_count)),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: mode,
+            game_conf: conf,
+        }
+    }
+
+    /// Creates new game where given text has to be typed, whitespace in text is turned into single spaces
+    pub fn from_text(mut conf: GameConf, text: &str) -> Game {
+        if let Limit::Time(t) = conf.limit {
+            conf.limit = Limit::Time(t.max(Duration::from_secs(1)));
+        }
+        Game {
+            correct_text: graphemes(&text.split_whitespace().collect::<Vec<&str>>().join(" ")),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: conf,
+        }
+    }
+
+    /// Creates new game with the same text and configuration
+    pub fn restart(&self) -> Game {
+        Game {
+            correct_text: self.correct_text.clone(),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: self.game_conf.clone(),
+        }
+    }
+
+    /// "Press" char key for written text
+    pub fn char_key_pressed(&mut self, c: char) {
+        self.type_char(c, false);
+    }
+
+    /// "Press" char key by holding it down, such runs are marked as invalid
+    pub fn char_key_repeated(&mut self, c: char) {
+        self.type_char(c, true);
+    }
+
+    fn type_char(&mut self, c: char, repeat: bool) {
+        if self.written_vec.len() >= self.correct_text.len() {
+            return; //nothing left to type
+        }
+        let expected = &self.correct_text[self.written_vec.len()];
+        let typed: String = format!("{}{}", self.pending, c).nfc().collect();
+        let at = self.get_time_passed();
+        self.statistics.keystrokes.push(Keystroke {
+            at,
+            typed: c,
+            expected: expected.chars().nth(self.pending.chars().count()).unwrap_or_default(),
+            repeat,
+        });
+        if *expected == typed {
+            self.pending.clear();
+            self.written_vec.push(Letter {
+                c: typed,
+                state: FieldState::Correct,
+            });
+            self.statistics.written_at.push(at);
+            self.statistics.correct_strokes += 1;
+        } else if expected.starts_with(&typed) {
+            self.pending = typed; //letter is typed with more than one key
+            self.statistics.correct_strokes += 1;
+        } else if self.game_conf.strictness == Strictness::Strict {
+            self.statistics.wrong_strokes += 1; //mistake is counted but not written
+        } else {
+            self.pending.clear();
+            self.written_vec.push(Letter {
+                c: typed,//?test for rewrite mode : self.correct_text[self.written_vec.len()].clone(),
+                state: FieldState::Wrong,
+            });
+            self.statistics.written_at.push(at);
+            self.statistics.wrong_strokes += 1;
+        }
+    }
+
+    /// char the next key press should type, `None` if whole text is typed
+    pub fn next_char(&self) -> Option<char> {
+        let expected = self.correct_text.get(self.written_vec.len())?;
+        expected.chars().nth(self.pending.chars().count())
+    }
+
+    /// Returns true if length of written text is the same as length of correct text
+    pub fn is_complete(&self) -> bool {
+        if self.written_vec.len() == self.correct_text.len() {
+            return true;
+        } //needed even for time limit
+
+        match self.game_conf.limit {
+            Limit::Time(t) => self.get_time_passed() >= t,
+            Limit::WordCount(count) => self.get_total_words_count() >= count, //?Possible problem if statistics are not updated, should update statistic after every input
+            Limit::None => false,
+        }
+    }
+
+    ///does everything needed to be done after the game ends
+    pub fn stop_game(&mut self) {
+        self.resume();
+    }
+
+    /// Stops game clock
+    pub fn pause(&mut self) {
+        if self.statistics.paused_at.is_none() {
+            self.statistics.paused_at = Some(SystemTime::now());
+        }
+    }
+
+    /// Continues stopped game clock, time spent in pause is not counted
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.statistics.paused_at.take() {
+            self.statistics.time_started += SystemTime::now()
+                .duration_since(paused_at)
+                .unwrap_or(Duration::from_secs(0));
+        }
+    }
+
+    /// Returns reasons why the run can't be trusted, empty if it looks fair
+    pub fn get_cheat_flags(&self) -> Vec<CheatFlag> {
+        validity::check_run(&self.statistics.keystrokes, self.statistics.pastes)
+    }
+
+    /// Counts pasted text, it is never typed into the game
+    pub fn reject_paste(&mut self) {
+        self.statistics.pastes += 1;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.statistics.paused_at.is_some()
+    }
+
+    /// "Press" backspace for written text, deletes correct strokes of the letter if it is correct
+    pub fn clear_last_letter(&mut self) {
+        if self.pending.pop().is_some() {
+            //chars of unfinished letter are always correct
+            self.statistics.correct_strokes = self.statistics.correct_strokes.saturating_sub(1);
+            return;
+        }
+        let letter = self.written_vec.pop();
+        self.statistics.written_at.truncate(self.written_vec.len());
+        if let Some(letter) = letter.filter(|l| l.state == FieldState::Correct) {
+            //needed to prevent abusive deleting and placing same letters for higher accuracy
+            self.statistics.correct_strokes =
+                self.statistics.correct_strokes.saturating_sub(letter.c.chars().count() as u32);
+        }
+    }
+    /// Deletes letters back to the start of the word
+    pub fn clear_last_world(&mut self) {
+        let word_starts: Vec<usize> = self.word_ranges().iter().map(|word| word.start).collect();
+        loop {
+            self.clear_last_letter();
+            let len = self.written_vec.len();
+            if self.pending.is_empty() && (len == 0 || word_starts.contains(&len)) {
+                break; //stop deleting letters if no letters left
+            }
+        }
+    }
+
+    /// Letter ranges of words in correct text split by Unicode word boundaries,
+    /// spaces and punctuation between words are not included
+    fn word_ranges(&self) -> Vec<Range<usize>> {
+        let text = self.correct_text.concat();
+        //byte where every letter starts
+        let mut starts = Vec::with_capacity(self.correct_text.len() + 1);
+        let mut byte = 0;
+        for letter in &self.correct_text {
+            starts.push(byte);
+            byte += letter.len();
+        }
+        starts.push(byte);
+        let letter_at = |byte: usize| starts.binary_search(&byte).unwrap_or_else(|i| i);
+        text.split_word_bound_indices()
+            .filter(|(_, word)| word.chars().any(|c| c.is_alphanumeric()))
+            .map(|(i, word)| letter_at(i)..letter_at(i + word.len()))
+            .collect()
+    }
+
+    /// Gets vector of letters including unfilled letters from correct text
+    /// Used only for rendering
+    ///
+    ///
+    pub fn get_written_vec(&mut self) -> Vec<Letter> {
+        let mut res = self.written_vec.clone();
+        for c in &self.correct_text[self.written_vec.len()..] {
+            res.push(Letter {
+                c: c.clone(),
+                state: FieldState::Unfilled,
+            });
+        }
+        res
+    }
+
+    //Gets random text for a game
+    // pub fn get_random_text(size: usize) -> String {
+    //     //crate::markov_gen::generate(size)
+    //     let mr = markov_rope::MarkovChain::default();
+    //     mr.generate_text(size as u32).unwrap() ////!TESTING
+    // }
+
+    /// Returns game statistics
+    pub fn get_statistics(&mut self) -> GameStat {
+        self.statistics = GameStat {
+            correct_strokes: self.statistics.correct_strokes,
+            wrong_strokes: self.statistics.wrong_strokes,
+            wrong_letters: self
+                .written_vec
+                .iter()
+                .filter(|letter| letter.state == FieldState::Wrong)
+                .count() as u32,
+            correct_words: self.get_correct_words_count(),
+            total_words: self.get_total_words_count(),
+            written_at: self.statistics.written_at.clone(),
+            word_times: self.correct_word_times(),
+            time_started: self.statistics.time_started,
+            time_finished: SystemTime::now(),
+            paused_at: None,
+            pastes: self.statistics.pastes,
+            keystrokes: self.statistics.keystrokes.clone(),
+            cheat_flags: self.get_cheat_flags(),
+        };
+        self.statistics.clone()
+    }
+    /// Counts words typed without mistakes, letter after the word has to be typed correctly too,
+    /// the last word of the text is counted when it is typed
+    pub fn get_correct_words_count(&self) -> u32 {
+        self.correct_word_times().len() as u32
+    }
+    /// Game time when every correctly typed word was finished, in order of the text
+    fn correct_word_times(&self) -> Vec<Duration> {
+        let len = self.written_vec.len();
+        self.word_ranges()
+            .iter()
+            .filter(|word| {
+                self.is_word_finished(word)
+                    && self.written_vec[word.start..(word.end + 1).min(len)]
+                        .iter()
+                        .all(|l| l.state == FieldState::Correct)
+            })
+            //letter finishing the word, the last letter of the text finishes the last word
+            .map(|word| self.statistics.written_at.get(word.end.min(len - 1)).copied().unwrap_or_default())
+            .collect()
+    }
+    /// Counts words with letter after them typed, the last word of the text is counted when it is typed
+    pub fn get_total_words_count(&self) -> u32 {
+        self.word_ranges().iter().filter(|word| self.is_word_finished(word)).count() as u32
+    }
+
+    /// word is finished by typing letter after it or by finishing the whole text
+    fn is_word_finished(&self, word: &Range<usize>) -> bool {
+        let len = self.written_vec.len();
+        word.end < len || (word.end == len && len == self.correct_text.len())
+    }
+
+    /// Returns time passed since the game started not counting pauses
+    pub fn get_time_passed(&self) -> Duration {
+        self.statistics
+            .paused_at
+            .unwrap_or(SystemTime::now())
+            .duration_since(self.statistics.time_started)
+            .unwrap_or(Duration::from_secs(0))
+    }
+
+    /// Returns correctly typed words per minute
+    pub fn get_wpm(&self) -> f64 {
+        let minutes = self.get_time_passed().as_secs_f64() / 60.0;
+        if minutes < 1.0 / 60.0 {
+            return 0.0; //first second gives too big numbers
+        }
+        self.get_correct_words_count() as f64 / minutes
+    }
+
+    /// Returns words per minute counting every typed letter, 5 letters is 1 word
+    pub fn get_raw_wpm(&self) -> f64 {
+        let minutes = self.get_time_passed().as_secs_f64() / 60.0;
+        if minutes < 1.0 / 60.0 {
+            return 0.0;
+        }
+        self.written_vec.len() as f64 / 5.0 / minutes
+    }
+
+    /// Returns percentage of correct strokes, 100 if nothing is typed yet
+    pub fn get_accuracy(&self) -> f64 {
+        let total = self.statistics.correct_strokes + self.statistics.wrong_strokes;
+        if total == 0 {
+            return 100.0;
+        }
+        self.statistics.correct_strokes as f64 / total as f64 * 100.0
+    }
+
+    /// Returns time left until time limit ends the game, `None` if game has no time limit
+    pub fn get_time_left(&self) -> Option<Duration> {
+        match self.game_conf.limit {
+            Limit::Time(t) => Some(t.saturating_sub(self.get_time_passed())),
+            _ => None,
+        }
+    }
+
+    /// Returns how much of the game is done, from 0.0 to 1.0
+    pub fn get_progress(&self) -> f64 {
+        let progress = match self.game_conf.limit {
+            Limit::Time(t) => self.get_time_passed().as_secs_f64() / t.as_secs_f64(),
+            Limit::WordCount(count) => self.get_total_words_count() as f64 / count as f64,
+            Limit::None => self.written_vec.len() as f64 / self.correct_text.len() as f64,
+        };
+        if progress.is_nan() {
+            return 0.0;
+        }
+        progress.clamp(0.0, 1.0)
+    }
+}
+/// counters collected during the game
+#[derive(Clone, Debug)]
+pub struct GameStat {
+    pub correct_strokes: u32,
+    pub wrong_strokes: u32,
+    pub wrong_letters: u32,
+    pub correct_words: u32,
+    pub total_words: u32,
+    pub time_started: SystemTime,
+    pub time_finished: SystemTime,
+    ///time when game was paused, `None` if game is not paused
+    pub paused_at: Option<SystemTime>,
+    ///attempts to paste text during the game
+    pub pastes: u32,
+    ///every typed char in order
+    pub keystrokes: Vec<Keystroke>,
+    ///game time when every letter of written text was typed
+    pub written_at: Vec<Duration>,
+    ///game time when every correct word was finished, filled by `Game::get_statistics`
+    pub word_times: Vec<Duration>,
+    ///reasons why the run can't be trusted, filled when game ends
+    pub cheat_flags: Vec<CheatFlag>,
+}
+impl GameStat {
+    pub fn new() -> GameStat {
+        GameStat {
+            correct_strokes: 0,
+            wrong_strokes: 0,
+            wrong_letters: 0,
+            correct_words: 0,
+            total_words: 0,
+            time_started: SystemTime::now(),
+            time_finished: SystemTime::now(),
+            paused_at: None,
+            pastes: 0,
+            keystrokes: Vec::new(),
+            written_at: Vec::new(),
+            word_times: Vec::new(),
+            cheat_flags: Vec::new(),
+        }
+    }
+}
+/// chars typed in given time in words per minute, 5 chars is 1 word
+fn per_minute(chars: u32, time: Duration) -> f64 {
+    words_per_minute(chars, time) / 5.0
+}
+/// words typed in given time per minute
+fn words_per_minute(words: u32, time: Duration) -> f64 {
+    if time.is_zero() {
+        0.0
+    } else {
+        words as f64 / (time.as_secs_f64() / 60.0)
+    }
+}
+impl GameStat {
+    /// game time, it is never shorter than time of the last keystroke
+    pub fn duration(&self) -> Duration {
+        self.time_finished
+            .duration_since(self.time_started)
+            .unwrap_or(Duration::from_secs(0))
+            .max(self.keystrokes.last().map_or(Duration::from_secs(0), |k| k.at))
+    }
+
+    /// correct words of the whole game per minute, the same as the last point of `speed_timeline`
+    /// and as `Game::get_wpm` at the end of the game
+    pub fn wpm(&self) -> f64 {
+        words_per_minute(self.word_times.len() as u32, self.duration())
+    }
+
+    /// splits the game into parts of `bucket` length and measures speed in every part
+    pub fn speed_timeline(&self, bucket: Duration) -> Vec<SpeedPoint> {
+        let total = self.duration();
+        if bucket.is_zero() || total.is_zero() || self.keystrokes.is_empty() {
+            return Vec::new();
+        }
+        let count = (total.as_secs_f64() / bucket.as_secs_f64()).ceil() as usize;
+        let mut points = Vec::new();
+        let mut keystrokes = self.keystrokes.iter().peekable();
+        for i in 0..count {
+            let start = bucket * i as u32;
+            let end = (start + bucket).min(total);
+            let mut chars = 0;
+            let mut errors = 0;
+            let last = i + 1 == count;
+            //last part takes everything left
+            while let Some(k) = keystrokes.next_if(|k| k.at < end || last) {
+                chars += 1;
+                if !k.is_correct() {
+                    errors += 1;
+                }
+            }
+            let words = self.word_times.iter().filter(|&&at| at < end || last).count();
+            points.push(SpeedPoint {
+                second: end.as_secs_f64(),
+                raw_wpm: per_minute(chars, end - start),
+                wpm: words_per_minute(words as u32, end),
+                errors,
+            });
+        }
+        points
+    }
+}
+/// speed during one part of the game
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpeedPoint {
+    ///seconds from start of the game to the end of this part
+    pub second: f64,
+    ///every char typed in this part, in words per minute
+    pub raw_wpm: f64,
+    ///correct words finished from start of the game per minute
+    pub wpm: f64,
+    pub errors: u32,
+}
+impl Default for GameStat {
+    fn default() -> Self {
+        GameStat::new()
+    }
+}
+
+/// one char key press
+#[derive(Debug, PartialEq, Clone)]
+pub struct Keystroke {
+    ///game time when key was pressed
+    pub at: Duration,
+    pub typed: char,
+    ///char of the text that should be typed
+    pub expected: char,
+    ///key was held down, not pressed
+    pub repeat: bool,
+}
+impl Keystroke {
+    pub fn is_correct(&self) -> bool {
+        self.typed == self.expected
+    }
+}
+
+/// typed letter and whether it matches the text
+#[derive(Debug, PartialEq, Clone)]
+pub struct Letter {
+    ///grapheme cluster, usually one char
+    pub c: String,
+    pub state: FieldState,
+}
+/// state of one letter of the text
+#[derive(PartialEq, Debug, Clone)]
+pub enum FieldState {
+    Unfilled,
+    Correct,
+    Wrong,
+}
+
+#[cfg(test)]
+mod test {
+    use std::{thread::sleep, time::{Duration, UNIX_EPOCH}};
+
+    use super::*;
+    #[test]
+    fn match_all_correct() {
+        let mut test_game = Game {
+            correct_text: graphemes("ccccc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: GameMode::Normal,
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        let written_text: Vec<char> = "ccccc".chars().collect();
+        for c in written_text {
+            test_game.char_key_pressed(c);
+        }
+        let answer = vec![
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+        ];
+        assert_eq!(test_game.get_written_vec(), answer);
+    }
+
+    #[test]
+    fn match_all_wrong() {
+        let mut test_game = Game {
+            correct_text: graphemes("wwwww"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: GameMode::Normal,
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        let written_text: Vec<char> = "-----".chars().collect();
+        for c in written_text {
+            test_game.char_key_pressed(c);
+        }
+        //wrong letter keeps the typed char, rewrite mode shows what was typed
+        let answer = vec![
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+        ];
+        assert_eq!(test_game.get_written_vec(), answer);
+    }
+
+    #[test]
+    fn match_mixed() {
+        let mut test_game = Game {
+            correct_text: graphemes("ccwcu"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: GameMode::Normal,
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        let written_text: Vec<char> = "cc-c".chars().collect();
+        for c in written_text {
+            test_game.char_key_pressed(c);
+        }
+        let answer = vec![
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "-".to_string(),
+                state: FieldState::Wrong,
+            },
+            Letter {
+                c: "c".to_string(),
+                state: FieldState::Correct,
+            },
+            Letter {
+                c: "u".to_string(),
+                state: FieldState::Unfilled,
+            },
+        ];
+        assert_eq!(test_game.get_written_vec(), answer);
+    }
+
+    #[test]
+    fn get_correct_words_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cc cc cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: GameMode::Normal,
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        let written_text: Vec<char> = "ccuuc cc cc".chars().collect();
+        for c in written_text {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.get_correct_words_count(), 2);
+    }
+
+    #[test]
+    fn word_times_test() {
+        let mut test_game = Game::from_text(GameConf::new(), "cc cc");
+        test_game.statistics.time_started = UNIX_EPOCH;
+        //stopped clock gives every key the time it is pressed at
+        for (millis, c) in [(100, 'c'), (200, 'c'), (300, 'x'), (500, 'c')] {
+            test_game.statistics.paused_at = Some(UNIX_EPOCH + Duration::from_millis(millis));
+            test_game.char_key_pressed(c);
+        }
+        test_game.clear_last_letter();
+        test_game.clear_last_letter();
+        for (millis, c) in [(700, ' '), (800, 'c'), (900, 'c')] {
+            test_game.statistics.paused_at = Some(UNIX_EPOCH + Duration::from_millis(millis));
+            test_game.char_key_pressed(c);
+        }
+        let stats = test_game.get_statistics();
+        assert_eq!(stats.written_at.len(), 5);
+        //word is finished by the space after it, the last word by its last letter
+        assert_eq!(stats.word_times, vec![Duration::from_millis(700), Duration::from_millis(900)]);
+        assert_eq!(stats.correct_words, 2);
+    }
+
+    #[test]
+    fn get_total_words_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cc cc cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            // game_mode: GameMode::Normal,
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        let written_text: Vec<char> = "ccuuc cc cc".chars().collect();
+        for c in written_text {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.get_total_words_count(), 4);
+    }
+
+    #[test]
+    fn get_accuracy_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cccc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+        };
+        assert_eq!(test_game.get_accuracy(), 100.0);
+        for c in "cc-c".chars() {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.get_accuracy(), 75.0);
+    }
+
+    #[test]
+    fn get_progress_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cc cc cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::WordCount(4),
+                ..GameConf::new()
+            },
+        };
+        assert_eq!(test_game.get_progress(), 0.0);
+        for c in "cc cc ".chars() {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.get_progress(), 0.5);
+    }
+
+    #[test]
+    fn strict_mode_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cccc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf {
+                strictness: Strictness::Strict,
+                ..GameConf::new()
+            },
+        };
+        for c in "c-c".chars() {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.written_vec.len(), 2);
+        assert_eq!(test_game.statistics.wrong_strokes, 1);
+    }
+
+    #[test]
+    fn file_text_source_test() {
+        let test_game = Game::new(
+            0,
+            GameConf {
+                limit: Limit::None,
+                text_source: TextSource::File("text.txt".to_string()),
+                ..GameConf::new()
+            },
+            Some("some  text\nfrom file ".to_string()),
+        );
+        assert_eq!(test_game.correct_text.concat(), "some text from file");
+    }
+
+    #[test]
+    fn restart_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf::new(),
+        };
+        for c in "c- ".chars() {
+            test_game.char_key_pressed(c);
+        }
+        let restarted = test_game.restart();
+        assert_eq!(restarted.correct_text, test_game.correct_text);
+        assert!(restarted.written_vec.is_empty());
+        assert_eq!(restarted.statistics.wrong_strokes, 0);
+    }
+
+    #[test]
+    fn pause_test() {
+        let mut test_game = Game {
+            correct_text: graphemes("cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf::new(),
+        };
+        test_game.pause();
+        let passed = test_game.get_time_passed();
+        sleep(Duration::from_millis(20));
+        assert_eq!(test_game.get_time_passed(), passed);
+        test_game.resume();
+        assert!(!test_game.is_paused());
+        assert!(test_game.get_time_passed() < Duration::from_millis(20));
+    }
+
+    #[test]
+    fn get_time_left_test() {
+        let mut test_game = Game::from_text(GameConf::new(), "cc cc");
+        test_game.pause();
+        let left = test_game.get_time_left().unwrap();
+        assert!(left <= Duration::from_secs(30) && left > Duration::from_secs(29));
+        test_game.game_conf.limit = Limit::WordCount(2);
+        assert_eq!(test_game.get_time_left(), None);
+    }
+
+    #[test]
+    fn speed_timeline_test() {
+        let mut stats = GameStat::new();
+        stats.time_finished = stats.time_started + Duration::from_millis(2500);
+        //one correct char every 100ms, every fifth char is wrong
+        stats.keystrokes = (0..25)
+            .map(|i| Keystroke {
+                at: Duration::from_millis(i * 100),
+                typed: if i % 5 == 4 { 'x' } else { 'c' },
+                expected: 'c',
+                repeat: false,
+            })
+            .collect();
+        //one correct word finished every 500ms
+        stats.word_times = (0..5).map(|i| Duration::from_millis(i * 500 + 400)).collect();
+        let points = stats.speed_timeline(Duration::from_secs(1));
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0].raw_wpm, 120.0); //10 chars in one second
+        assert_eq!(points[0].wpm, 120.0); //2 words in one second
+        assert_eq!(points[1].wpm, 120.0);
+        assert_eq!(points[0].errors, 2);
+        assert_eq!(points[2].second, 2.5);
+        assert_eq!(points[2].raw_wpm, 120.0); //5 chars in half a second
+        assert_eq!(points.iter().map(|p| p.errors).sum::<u32>(), 5);
+        assert_eq!(points[2].wpm, 120.0); //5 words in 2.5 seconds
+        assert_eq!(stats.wpm(), points[2].wpm);
+        assert!(GameStat::new().speed_timeline(Duration::from_secs(1)).is_empty());
+    }
+
+    #[test]
+    fn grapheme_clusters_test() {
+        let conf = GameConf {
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        //accent is joined with its letter, emoji is one letter made of three chars
+        let mut test_game = Game::from_text(conf, "cafe\u{301} 👩\u{200d}💻!");
+        assert_eq!(test_game.correct_text.len(), 7);
+        assert_eq!(test_game.correct_text[3], "é");
+        for c in "café 👩\u{200d}".chars() {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.written_vec.len(), 5);
+        assert_eq!(test_game.pending, "👩\u{200d}");
+        assert_eq!(test_game.next_char(), Some('💻'));
+        test_game.char_key_pressed('💻');
+        assert_eq!(test_game.written_vec[5].state, FieldState::Correct);
+        assert_eq!(test_game.statistics.correct_strokes, 8);
+        test_game.clear_last_letter();
+        assert_eq!(test_game.statistics.correct_strokes, 5);
+        //wrong char in the middle of the letter makes the whole letter wrong
+        for c in "👩x".chars() {
+            test_game.char_key_pressed(c);
+        }
+        assert_eq!(test_game.written_vec[5].c, "👩x");
+        assert_eq!(test_game.written_vec[5].state, FieldState::Wrong);
+    }
+
+    #[test]
+    fn unicode_words_test() {
+        let conf = GameConf {
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        let mut test_game = Game::from_text(conf, "hello, world! 你好");
+        for c in "hellp, world! 你好".chars() {
+            test_game.char_key_pressed(c);
+        }
+        //words end before punctuation, every CJK letter is a word
+        assert_eq!(test_game.get_total_words_count(), 4);
+        assert_eq!(test_game.get_correct_words_count(), 3);
+        test_game.clear_last_world();
+        assert_eq!(test_game.written_vec.len(), 15);
+        for _ in 0..2 {
+            test_game.clear_last_world();
+        }
+        assert_eq!(test_game.written_vec.len(), 7);
+    }
+
+    #[ignore = "makes thread sleepy -_- zzz"]
+    #[test]
+    fn get_time_test() {
+        let mut test_game = Game::new(
+            90,
+            GameConf {
+                mode: GameMode::Normal,
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+            None,
+        );
+        let pass_dur = Duration::new(2, 0);
+        sleep(pass_dur);
+
+        let stats = test_game.get_statistics();
+        let time_passed = stats
+            .time_finished
+            .duration_since(stats.time_started)
+            .expect("error calculating time thing idk");
+        assert!(time_passed.checked_sub(pass_dur).unwrap() < Duration::from_secs_f32(0.001));
+    }
+}
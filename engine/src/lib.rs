@@ -0,0 +1,27 @@
+//! Typing game engine without any terminal code.
+//!
+//! [`Game`] keeps the text that has to be typed and everything that was typed so far,
+//! front-ends pass key presses to it and render [`Game::get_written_vec`].
+//!
+//! ```
+//! use typing_engine::{FieldState, Game, GameConf, Limit};
+//!
+//! let conf = GameConf {
+//!     limit: Limit::None,
+//!     ..GameConf::new()
+//! };
+//! let mut game = Game::from_text(conf, "hello world");
+//! for c in "hello".chars() {
+//!     game.char_key_pressed(c);
+//! }
+//! assert_eq!(game.get_written_vec()[0].state, FieldState::Correct);
+//! assert!(!game.is_complete());
+//! ```
+
+pub mod game;
+pub mod markov;
+pub mod validity;
+
+pub use game::{graphemes, FieldState, Game, GameConf, GameMode, GameStat, Keystroke, Letter, Limit, SpeedPoint, Strictness, TextSource};
+pub use markov::MarkovChain;
+pub use validity::CheatFlag;
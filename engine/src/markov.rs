@@ -0,0 +1,116 @@
+//! Word level Markov chain used to generate text for the game.
+
+use std::collections::HashMap;
+
+use rand::Rng;
+
+/// chain of words built from corpus, next word is picked by how often it follows the previous one
+#[derive(Clone, Debug, Default)]
+pub struct MarkovChain {
+    ///every distinct word of corpus
+    words: Vec<String>,
+    ///for every word: words that follow it and how many times
+    followers: Vec<Vec<(usize, u32)>>,
+    ///words used to start and when chain has nowhere to go
+    word_list: Vec<String>,
+}
+impl MarkovChain {
+    /// builds chain from corpus, `word_list` is used to start text and to go on after dead ends
+    pub fn new(corpus: &str, word_list: &[String]) -> MarkovChain {
+        let mut chain = MarkovChain {
+            word_list: word_list.iter().filter(|w| !w.trim().is_empty()).map(|w| w.trim().to_string()).collect(),
+            ..MarkovChain::default()
+        };
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut previous: Option<usize> = None;
+        for word in corpus.split_whitespace() {
+            let i = *index.entry(word).or_insert_with(|| {
+                chain.words.push(word.to_string());
+                chain.followers.push(Vec::new());
+                chain.words.len() - 1
+            });
+            if let Some(prev) = previous {
+                match chain.followers[prev].iter_mut().find(|(next, _)| *next == i) {
+                    Some((_, count)) => *count += 1,
+                    None => chain.followers[prev].push((i, 1)),
+                }
+            }
+            previous = Some(i);
+        }
+        chain
+    }
+
+    /// true if chain can't generate any word
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.word_list.is_empty()
+    }
+
+    /// generates text with given amount of words, every word is followed by space
+    pub fn generate(&self, word_count: u32) -> String {
+        let mut rng = rand::thread_rng();
+        let index: HashMap<&str, usize> = self.words.iter().enumerate().map(|(i, w)| (w.as_str(), i)).collect();
+        let mut text = String::new();
+        let mut current: Option<usize> = None;
+        for _ in 0..word_count {
+            let next = current.and_then(|i| self.pick_follower(i, &mut rng));
+            let word = match next {
+                Some(i) => self.words[i].as_str(),
+                None if !self.word_list.is_empty() => self.word_list[rng.gen_range(0..self.word_list.len())].as_str(),
+                None if !self.words.is_empty() => self.words[rng.gen_range(0..self.words.len())].as_str(),
+                None => break,
+            };
+            current = index.get(word).copied();
+            text.push_str(word);
+            text.push(' ');
+        }
+        text
+    }
+
+    fn pick_follower(&self, word: usize, rng: &mut impl Rng) -> Option<usize> {
+        let followers = &self.followers[word];
+        let total: u32 = followers.iter().map(|(_, count)| count).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = rng.gen_range(0..total);
+        for (next, count) in followers {
+            if roll < *count {
+                return Some(*next);
+            }
+            roll -= count;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn generated_words_follow_corpus() {
+        let chain = MarkovChain::new("one two three\nfour", &[]);
+        let text = chain.generate(12);
+        assert_eq!(text.split_whitespace().count(), 12);
+        assert!(text.ends_with(' '));
+        //every word except dead end "four" is followed by the next word of corpus
+        let words: Vec<&str> = text.split_whitespace().collect();
+        for pair in words.windows(2) {
+            match pair[0] {
+                "one" => assert_eq!(pair[1], "two"),
+                "two" => assert_eq!(pair[1], "three"),
+                "three" => assert_eq!(pair[1], "four"),
+                _ => (),
+            }
+        }
+    }
+
+    #[test]
+    fn word_list_is_used_after_dead_end() {
+        let chain = MarkovChain::new("end", &["start".to_string()]);
+        let text = chain.generate(4);
+        assert_eq!(text, "start start start start ");
+        assert!(MarkovChain::new("", &[]).is_empty());
+        assert_eq!(MarkovChain::new(" \n", &[]).generate(3), "");
+    }
+}
@@ -0,0 +1,112 @@
+//! Checks that a run was typed by a human.
+
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::Keystroke;
+
+/// keys pressed faster than this one after another are not typed by hand
+pub const MIN_KEY_INTERVAL: Duration = Duration::from_millis(10);
+/// how many too fast intervals in a row make a run invalid, single ones happen with key rollover
+pub const MAX_FAST_INTERVALS: usize = 4;
+/// highest believable average speed in chars per second (300 WPM)
+pub const MAX_CHARS_PER_SECOND: f64 = 25.0;
+/// average speed is checked only for runs with at least this many keystrokes
+const MIN_KEYSTROKES_FOR_SPEED: usize = 20;
+
+/// reason why run can't be trusted
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CheatFlag {
+    ///keys were pressed faster than human can type
+    SuperhumanSpeed,
+    ///key was held down and repeated by keyboard
+    KeyRepeat,
+    ///text was pasted
+    Paste,
+}
+impl CheatFlag {
+    pub fn description(&self) -> &'static str {
+        match self {
+            CheatFlag::SuperhumanSpeed => "superhuman speed",
+            CheatFlag::KeyRepeat => "held down keys",
+            CheatFlag::Paste => "pasted text",
+        }
+    }
+}
+
+/// finds everything suspicious in keystroke timeline
+pub fn check_run(keystrokes: &[Keystroke], pastes: u32) -> Vec<CheatFlag> {
+    let mut flags = Vec::new();
+    if has_fast_burst(keystrokes) || average_too_fast(keystrokes) {
+        flags.push(CheatFlag::SuperhumanSpeed);
+    }
+    if keystrokes.iter().any(|k| k.repeat) {
+        flags.push(CheatFlag::KeyRepeat);
+    }
+    if pastes > 0 {
+        flags.push(CheatFlag::Paste);
+    }
+    flags
+}
+
+/// many keys in a row with too short intervals, text pasted as key presses looks like this
+fn has_fast_burst(keystrokes: &[Keystroke]) -> bool {
+    let mut fast_in_row = 0;
+    for pair in keystrokes.windows(2) {
+        if pair[1].at.saturating_sub(pair[0].at) < MIN_KEY_INTERVAL {
+            fast_in_row += 1;
+            if fast_in_row >= MAX_FAST_INTERVALS {
+                return true;
+            }
+        } else {
+            fast_in_row = 0;
+        }
+    }
+    false
+}
+
+fn average_too_fast(keystrokes: &[Keystroke]) -> bool {
+    if keystrokes.len() < MIN_KEYSTROKES_FOR_SPEED {
+        return false;
+    }
+    let time = keystrokes[keystrokes.len() - 1].at.saturating_sub(keystrokes[0].at);
+    (keystrokes.len() - 1) as f64 / time.as_secs_f64() > MAX_CHARS_PER_SECOND
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// keystrokes of text typed with the same interval between keys
+    fn timeline(text: &str, interval: Duration) -> Vec<Keystroke> {
+        text.chars()
+            .enumerate()
+            .map(|(i, c)| Keystroke {
+                at: interval * i as u32,
+                typed: c,
+                expected: c,
+                repeat: false,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn human_run_is_valid() {
+        let mut keystrokes = timeline("the quick brown fox jumps over the lazy dog", Duration::from_millis(90));
+        //key rollover gives a few very short intervals
+        keystrokes[5].at = keystrokes[4].at + Duration::from_millis(2);
+        assert!(check_run(&keystrokes, 0).is_empty());
+    }
+
+    #[test]
+    fn suspicious_runs() {
+        let burst = timeline("pasted", Duration::from_millis(1));
+        assert_eq!(check_run(&burst, 0), vec![CheatFlag::SuperhumanSpeed]);
+        let fast = timeline("the quick brown fox jumps over the lazy dog", Duration::from_millis(30));
+        assert_eq!(check_run(&fast, 0), vec![CheatFlag::SuperhumanSpeed]);
+        let mut held = timeline("aaaa", Duration::from_millis(100));
+        held[3].repeat = true;
+        assert_eq!(check_run(&held, 2), vec![CheatFlag::KeyRepeat, CheatFlag::Paste]);
+    }
+}
@@ -0,0 +1,90 @@
+use std::time::Duration;
+
+use typing_engine::{FieldState, Game, GameConf, GameMode, Limit, Strictness, TextSource};
+
+fn type_text(game: &mut Game, text: &str) {
+    for c in text.chars() {
+        game.char_key_pressed(c);
+    }
+}
+
+#[test]
+fn whole_text_game_completes() {
+    let conf = GameConf {
+        limit: Limit::None,
+        ..GameConf::new()
+    };
+    let mut game = Game::from_text(conf, "one two three");
+    type_text(&mut game, "one two thre");
+    assert!(!game.is_complete());
+    type_text(&mut game, "e");
+    assert!(game.is_complete());
+
+    game.stop_game();
+    let stats = game.get_statistics();
+    assert_eq!(stats.correct_strokes, 13);
+    assert_eq!(stats.wrong_strokes, 0);
+    assert_eq!(stats.correct_words, 3);
+}
+
+#[test]
+fn word_limit_ends_game() {
+    let conf = GameConf {
+        limit: Limit::WordCount(2),
+        ..GameConf::new()
+    };
+    let mut game = Game::from_text(conf, "aa bb cc dd");
+    type_text(&mut game, "aa bb");
+    assert!(!game.is_complete());
+    type_text(&mut game, " ");
+    assert!(game.is_complete());
+}
+
+#[test]
+fn mistakes_and_corrections() {
+    let mut game = Game::from_text(GameConf::new(), "abc def");
+    type_text(&mut game, "abx");
+    let letters = game.get_written_vec();
+    assert_eq!(letters[2].state, FieldState::Wrong);
+    assert_eq!(letters[3].state, FieldState::Unfilled);
+
+    game.clear_last_letter();
+    type_text(&mut game, "c");
+    assert_eq!(game.get_written_vec()[2].state, FieldState::Correct);
+    assert_eq!(game.get_statistics().wrong_strokes, 1);
+}
+
+#[test]
+fn strict_game_does_not_move_on_mistakes() {
+    let conf = GameConf {
+        strictness: Strictness::Strict,
+        ..GameConf::new()
+    };
+    let mut game = Game::from_text(conf, "abc");
+    type_text(&mut game, "axbc");
+    assert!(game.is_complete());
+    assert_eq!(game.get_statistics().wrong_strokes, 1);
+}
+
+#[test]
+fn generated_text_has_requested_word_count() {
+    let conf = GameConf {
+        mode: GameMode::Rewrite,
+        limit: Limit::WordCount(10),
+        text_source: TextSource::Generated,
+        ..GameConf::new()
+    };
+    let game = Game::new(0, conf, Some("a b c d e f g h".to_string()));
+    let text: String = game.correct_text.concat();
+    assert!(text.split(' ').count() >= 10);
+}
+
+#[test]
+fn zero_time_limit_is_raised_to_one_second() {
+    let conf = GameConf {
+        limit: Limit::Time(Duration::from_secs(0)),
+        ..GameConf::new()
+    };
+    let game = Game::from_text(conf, "text");
+    assert_eq!(game.game_conf.limit, Limit::Time(Duration::from_secs(1)));
+}
@@ -1,52 +1,126 @@
-use std::time::{Duration, SystemTime, SystemTimeError};
+use std::time::{Duration, SystemTime};
 
-use crossterm::event::KeyModifiers;
-use ratatui::layout::{Constraint, Direction, Layout};
+use crossterm::event::{KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::widgets::block::{Position, Title};
+use ratatui::widgets::Gauge;
+use unicode_width::UnicodeWidthStr;
 
+use crate::config_manager::{HudPlacement, Settings};
+use crate::layout::KeyboardLayout;
+use crate::keymap::{Action, KeyContext};
+use crate::tui;
+
+use super::keyboard::{self, KEYBOARD_HEIGHT};
 use super::*;
 
+///quick actions shown in game window
+const KEY_HINTS: [(&[Action], &str); 4] = [
+    (&[Action::Restart], "restart"),
+    (&[Action::NextText], "new text"),
+    (&[Action::GameConfig], "configuration"),
+    (&[Action::Pause], "pause"),
+];
+///how long key of wrong letter is red on on-screen keyboard
+const MISTAKE_FLASH: Duration = Duration::from_millis(300);
+
 /// game component - responsible for game window behavior
 #[derive(Debug)]
 pub struct GameComp {
     pub game: Game,
-    pub last_stat_time: SystemTime,
-    pub stat_update_period: Duration,
+    pub settings: Settings,
+    ///values shown in HUD, updated every tick
+    pub hud_stats: HudStats,
+    ///layout shown on on-screen keyboard, `None` if keyboard is hidden
+    pub keyboard: Option<KeyboardLayout>,
+    ///last wrongly typed char and when it was typed
+    pub mistake: Option<(char, SystemTime)>,
+    ///problem that doesn't stop the game, like configuration that can't be saved
+    pub status: Option<String>,
+}
+/// live game statistics shown in HUD
+#[derive(Debug, Default, Clone)]
+pub struct HudStats {
+    pub wpm: f64,
+    pub raw_wpm: f64,
+    pub accuracy: f64,
+    pub progress: f64,
+}
+impl HudStats {
+    pub fn from_game(game: &Game) -> HudStats {
+        HudStats {
+            wpm: game.get_wpm(),
+            raw_wpm: game.get_raw_wpm(),
+            accuracy: game.get_accuracy(),
+            progress: game.get_progress(),
+        }
+    }
 }
 impl Component for GameComp {
     /// react to message and respond
     fn handle_message(&mut self, msg: Message) -> Message {
         let answer: Option<Message> = match msg {
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::StopGame),
+                Action::Restart => Some(Message::RestartGame(self.game.clone())),
+                Action::NextText => Some(Message::StartGame(self.game.game_conf.clone())),
+                Action::GameConfig => Some(Message::OpenGameConfig),
+                Action::Pause => {
+                    if self.game.is_paused() {
+                        self.game.resume();
+                    } else {
+                        self.game.pause();
+                    }
+                    None
+                }
+                Action::DeleteChar => {
+                    self.game.clear_last_letter();
+                    None
+                }
+                Action::DeleteWord => {
+                    self.game.clear_last_world();
+                    None
+                }
+                _ => None,
+            },
             Message::KeyInput(key) => match key.code {
-                KeyCode::Esc => Some(Message::StopGame),
-                KeyCode::Char(c) => {
-                    self.game.char_key_pressed(c);
+                KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+                    self.game.resume(); //typing continues paused game
+                    let wrong_strokes = self.game.statistics.wrong_strokes;
+                    if key.kind == KeyEventKind::Repeat {
+                        self.game.char_key_repeated(c);
+                    } else {
+                        self.game.char_key_pressed(c);
+                    }
+                    if self.game.statistics.wrong_strokes > wrong_strokes {
+                        self.mistake = Some((c, SystemTime::now()));
+                        if self.settings.sound.bell_on_error {
+                            tui::bell();
+                        }
+                    }
                     if self.game.is_complete() {
                         return Message::StopGame;
                     };
                     None
                 }
-                KeyCode::Backspace => {
-                    match key.modifiers {
-                        KeyModifiers::CONTROL => self.game.clear_last_world(),
-                        _ => self.game.clear_last_letter(),
-                    }
-                    None
-                }
                 _ => None,
             },
+            Message::Paste(_) => {
+                self.game.reject_paste(); //pasted text is never typed
+                None
+            }
+            Message::FocusLost => {
+                self.game.pause();
+                None
+            }
             Message::Tick => {
+                if self.game.is_paused() {
+                    return msg;
+                }
                 if self.game.is_complete() {
                     return Message::StopGame;
                 };
-                if SystemTime::now()
-                    .duration_since(self.last_stat_time)
-                    .or::<SystemTimeError>(Ok(Duration::from_secs(0)))
-                    .unwrap()
-                    > self.stat_update_period
-                {
-                    self.last_stat_time = SystemTime::now();
-                    self.game.register_speed()
-                }
+                self.hud_stats = HudStats::from_game(&self.game);
                 None
             }
             _ => None,
@@ -56,6 +130,27 @@ impl Component for GameComp {
             None => msg,
         }
     }
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Typing
+    }
+    fn next_tick(&self) -> Option<SystemTime> {
+        if self.game.is_paused() {
+            return None;
+        }
+        let now = SystemTime::now();
+        let passed = self.game.get_time_passed();
+        //time left and HUD change every full second of the game
+        let next_second = now + (Duration::from_secs(passed.as_secs() + 1) - passed);
+        let mut next = next_second;
+        if let Some(left) = self.game.get_time_left() {
+            next = next.min(now + left);
+        }
+        //red key goes back to normal
+        if let Some((_, at)) = self.mistake.filter(|(_, at)| *at + MISTAKE_FLASH > now) {
+            next = next.min(at + MISTAKE_FLASH);
+        }
+        Some(next)
+    }
     /// render window
     fn view(&mut self, f: &mut Frame) {
         match self.game.game_conf.mode {
@@ -65,16 +160,65 @@ impl Component for GameComp {
     }
 }
 impl GameComp {
-    pub fn new(
-        mut game: Game,
-        last_stat_time: SystemTime,
-        stat_update_period: Duration,
-    ) -> GameComp {
-        game.statistics.intervals = stat_update_period.as_secs() as i32;
+    pub fn new(game: Game, settings: Settings, keyboard: Option<KeyboardLayout>) -> GameComp {
         GameComp {
+            hud_stats: HudStats::from_game(&game),
             game,
-            last_stat_time,
-            stat_update_period,
+            settings,
+            keyboard,
+            mistake: None,
+            status: None,
+        }
+    }
+    /// char whose key flashes on on-screen keyboard
+    fn flashing_mistake(&self) -> Option<char> {
+        self.mistake
+            .filter(|(_, at)| SystemTime::now().duration_since(*at).unwrap_or_default() < MISTAKE_FLASH)
+            .map(|(c, _)| c)
+    }
+    /// splits HUD row from area according to HUD placement, returns HUD area and what is left
+    fn split_hud(&self, area: Rect) -> (Option<Rect>, Rect) {
+        match self.settings.hud.placement {
+            HudPlacement::Top => {
+                let layout = Layout::vertical([Constraint::Length(1), Constraint::Min(0)]).split(area);
+                (Some(layout[0]), layout[1])
+            }
+            HudPlacement::Bottom => {
+                let layout = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(area);
+                (Some(layout[1]), layout[0])
+            }
+            HudPlacement::Hidden => (None, area),
+        }
+    }
+    /// render live statistics and progress bar
+    fn hud_view(&self, f: &mut Frame, area: Rect) {
+        let mut items: Vec<String> = Vec::new();
+        if self.settings.hud.show_wpm {
+            items.push(format!("WPM: {}", self.hud_stats.wpm.round()));
+        }
+        if self.settings.hud.show_raw_wpm {
+            items.push(format!("Raw: {}", self.hud_stats.raw_wpm.round()));
+        }
+        if self.settings.hud.show_accuracy {
+            items.push(format!("Accuracy: {}%", self.hud_stats.accuracy.round()));
+        }
+        let layout = if self.settings.hud.show_progress {
+            Layout::horizontal([Constraint::Min(0), Constraint::Percentage(30)]).split(area)
+        } else {
+            Layout::horizontal([Constraint::Min(0)]).split(area)
+        };
+        f.render_widget(
+            Paragraph::new(items.join(" | ")).style(Style::new().white().on_black()),
+            layout[0],
+        );
+        if self.settings.hud.show_progress {
+            f.render_widget(
+                Gauge::default()
+                    .ratio(self.hud_stats.progress)
+                    .label(format!("{}%", (self.hud_stats.progress * 100.0).round()))
+                    .gauge_style(Style::new().green().on_black()),
+                layout[1],
+            );
         }
     }
     /// render normal view
@@ -82,21 +226,25 @@ impl GameComp {
         let write_field_rows = 3; //height of field where text is displayed //TODO: make it configurable in game settings
 
         let matched_letter_vec = self.game.get_written_vec(); //vector of written characters
-        let text_width = (f.size().width - 5) as usize; //width of field where text is displayed
+        let text_width = f.size().width.saturating_sub(5) as usize; //width of field where text is displayed
 
         let mut line: Vec<Span> = Vec::new();
         let mut text: Vec<Line> = Vec::new();
         let mut unfilled_started = false;
 
-        let mut letter_in_line_count = 0;
+        let mut line_width = 0; //columns taken in current line
         let mut current_word: Vec<Span> = Vec::new(); //currently processed word, it will be added to the line and line will be added to the text
+        let mut current_word_width = 0;
 
         let mut character_count = 0;
         for (i,letter) in matched_letter_vec.iter().enumerate() {
             character_count += 1;
+            let shown = &self.game.correct_text[i];
+            let width = shown.width(); //CJK letters take two columns
+            current_word_width += width;
             //creating Span representing one character colored in its state colors
             current_word.push(Span::styled(
-                format!("{}", self.game.correct_text[i]),
+                shown.clone(),
                 match letter.state {
                     FieldState::Unfilled if !unfilled_started => {
                         unfilled_started = true;
@@ -105,7 +253,7 @@ impl GameComp {
                     FieldState::Unfilled => Style::new().gray().not_underlined(),
                     FieldState::Correct => Style::new().green().not_underlined(),
                     FieldState::Wrong => {
-                        if letter.c == ' ' {
+                        if letter.c == " " {
                             Style::new()
                                 .red()
                                 .underlined()
@@ -117,11 +265,11 @@ impl GameComp {
                 },
             ));
 
-            //wrapping on new word
-            if letter.c == ' ' {
+            //wrapping on new word, text without spaces can be wrapped after every wide letter
+            if letter.c == " " || width > 1 {
                 //creating new line if the word cannot fit in previous line
-                if letter_in_line_count + current_word.len() >= text_width {
-                    letter_in_line_count = 0;
+                if line_width + current_word_width >= text_width {
+                    line_width = 0;
                     text.push(Line::from(line));
                     line = Vec::new();
 
@@ -133,10 +281,11 @@ impl GameComp {
                 }
 
                 //adding completed word to the line
-                letter_in_line_count += current_word.len();
+                line_width += current_word_width;
+                current_word_width = 0;
                 line.append(&mut current_word);
             }
-            if character_count + 1 == matched_letter_vec.len() {
+            if character_count == matched_letter_vec.len() {
                 line.append(&mut current_word);
                 text.push(Line::from(line.clone()));
             }
@@ -145,9 +294,9 @@ impl GameComp {
         let y_center_layout = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length((f.size().height - (write_field_rows as u16 + 2)) / 2),
+                Constraint::Length(f.size().height.saturating_sub(write_field_rows as u16 + 2) / 2),
                 Constraint::Length(write_field_rows as u16 + 2),
-                Constraint::Length((f.size().height - (write_field_rows as u16 + 2)) / 2),
+                Constraint::Length(f.size().height.saturating_sub(write_field_rows as u16 + 2) / 2),
             ])
             .split(f.size());
         //fully centered layout
@@ -155,32 +304,34 @@ impl GameComp {
             .direction(Direction::Horizontal)
             .constraints([
                 Constraint::Length(1),
-                Constraint::Length(f.size().width - 3),
+                Constraint::Length(f.size().width.saturating_sub(3)),
                 Constraint::Length(1),
             ])
             .split(y_center_layout[1]);
 
-        //writing time/words left as border title
-
-        match self.game.game_conf.limit{
+        //writing time/words left as border title, key hints are on bottom border so they never cover it
+        let limit_title = match self.game.game_conf.limit {
             crate::game::Limit::Time(t) => {
-                let time_passed = SystemTime::now().duration_since(self.game.statistics.time_started);
-                if let Ok(val) = time_passed{
-                    let time_left =  (t - val).as_secs();
-                    f.render_widget(Block::new().title(format!("{}s",time_left)).borders(Borders::ALL), f.size());
-                }else{
-                    f.render_widget(Block::new().title("Border").borders(Borders::ALL), f.size());
-                }
-            },
+                let time_left = t.saturating_sub(self.game.get_time_passed()).as_secs();
+                format!("{}s", time_left)
+            }
             crate::game::Limit::WordCount(c) => {
-                let words_left = c - self.game.get_total_words_count();
-                f.render_widget(Block::new().title(format!("{}/{}",words_left,c)).borders(Borders::ALL), f.size());
-
-            },
-            crate::game::Limit::None => {
-                f.render_widget(Block::new().title("Border").borders(Borders::ALL), f.size());
-            },
+                let words_left = c.saturating_sub(self.game.get_total_words_count());
+                format!("{}/{}", words_left, c)
+            }
+            crate::game::Limit::None => "Border".to_string(),
+        };
+        let mut border = Block::new()
+            .title(limit_title)
+            .title(Title::from(self.settings.input.keymap.hints(KeyContext::Typing, &KEY_HINTS)).position(Position::Bottom).alignment(Alignment::Right))
+            .borders(Borders::ALL);
+        if self.game.is_paused() {
+            border = border.title(Title::from("paused, type to continue").alignment(Alignment::Center));
+        }
+        if let Some(status) = &self.status {
+            border = border.title(Title::from(status.as_str()).position(Position::Bottom).alignment(Alignment::Left));
         }
+        f.render_widget(border, f.size());
 
 
         f.render_widget(
@@ -191,6 +342,17 @@ impl GameComp {
                 .alignment(Alignment::Left),
             centered_layout[1],
         );
+
+        if let (Some(hud_area), _) = self.split_hud(Block::bordered().inner(f.size())) {
+            self.hud_view(f, hud_area);
+        }
+        if let Some(layout) = &self.keyboard {
+            //one empty row under text field, border and HUD row are left free
+            let below = y_center_layout[2];
+            let reserved = if self.settings.hud.placement == HudPlacement::Bottom { 3 } else { 2 };
+            let area = Rect::new(below.x + 1, below.y + 1, below.width.saturating_sub(2), below.height.saturating_sub(reserved));
+            keyboard::keyboard_view(f, area, layout, self.game.next_char(), self.flashing_mistake());
+        }
     }
 
     ///render rewrite mode view
@@ -201,7 +363,7 @@ impl GameComp {
         let mut unfilled_started = false;
         for letter in matched_letter_vec {
             text.push(Span::styled(
-                format!("{}", letter.c),
+                letter.c.clone(),
                 match letter.state {
                     FieldState::Unfilled if !unfilled_started => {
                         unfilled_started = true;
@@ -210,7 +372,7 @@ impl GameComp {
                     FieldState::Unfilled => break,
                     FieldState::Correct => Style::new().green().not_underlined(),
                     FieldState::Wrong => {
-                        if letter.c == ' ' {
+                        if letter.c == " " {
                             Style::new()
                                 .red()
                                 .underlined()
@@ -224,15 +386,31 @@ impl GameComp {
         }
         let text: Line = Line::from(text);
 
+        let (hud_area, mut game_area) = self.split_hud(f.size());
+        if let Some(hud_area) = hud_area {
+            self.hud_view(f, hud_area);
+        }
+        if let Some(layout) = &self.keyboard {
+            let layout_rows = Layout::vertical([Constraint::Min(0), Constraint::Length(KEYBOARD_HEIGHT)]).split(game_area);
+            keyboard::keyboard_view(f, layout_rows[1], layout, self.game.next_char(), self.flashing_mistake());
+            game_area = layout_rows[0];
+        }
         let layout = Layout::new(
             Direction::Vertical,
             [Constraint::Percentage(50), Constraint::Percentage(50)],
         )
-        .split(f.size());
+        .split(game_area);
 
+        let mut typed_block = Block::new()
+            .title(if self.game.is_paused() { "You type here (paused, type to continue)" } else { "You type here" })
+            .title(Title::from(self.settings.input.keymap.hints(KeyContext::Typing, &KEY_HINTS)).position(Position::Bottom).alignment(Alignment::Right))
+            .borders(Borders::ALL);
+        if let Some(status) = &self.status {
+            typed_block = typed_block.title(Title::from(status.as_str()).position(Position::Bottom).alignment(Alignment::Left));
+        }
         f.render_widget(
             Paragraph::new(text)
-                .block(Block::new().title("You type here").borders(Borders::ALL))
+                .block(typed_block)
                 .style(Style::new().white().on_black())
                 .alignment(Alignment::Left)
                 .wrap(Wrap { trim: false }),
@@ -240,12 +418,7 @@ impl GameComp {
         );
         f.render_widget(
             Paragraph::new(
-                self.game
-                    .correct_text
-                    .iter()
-                    .map(|x| x.to_string())
-                    .collect::<Vec<String>>()
-                    .concat(),
+                self.game.correct_text.concat(),
             )
             .block(
                 Block::new()
@@ -259,3 +432,195 @@ impl GameComp {
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    use crate::game::{GameConf, GameMode, Limit, TextSource};
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::history::RunRecord;
+    use crate::model::Model;
+    use crossterm::event::KeyEvent;
+    use typing_engine::CheatFlag;
+
+    #[test]
+    fn key_hints_do_not_cover_time_left() {
+        let dir = TestDir::new("key_hints_do_not_cover_time_left");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 40, 12);
+        let conf = GameConf {
+            limit: Limit::Time(Duration::from_secs(30)),
+            ..GameConf::new()
+        };
+        let game = Game::from_text(conf, "the quick brown fox");
+        runner.model.active_window =
+            WindowType::Game(GameComp::new(game, dir.settings(), None));
+        let screen = runner.screen();
+        //countdown shows whole seconds left
+        assert!(screen.starts_with("┌29s") || screen.starts_with("┌30s"));
+        assert!(screen.lines().last().unwrap().contains("Tab = restart"));
+    }
+    #[test]
+    fn key_hints_follow_keymap() {
+        let dir = TestDir::new("key_hints_follow_keymap");
+        let mut settings = dir.settings();
+        settings.input.keymap.typing.insert("F5".to_string(), Action::Restart);
+        settings.input.keymap.typing.remove("Ctrl+p");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 120, 12);
+        let game = Game::from_text(GameConf::new(), "the quick brown fox");
+        runner.model.active_window =
+            WindowType::Game(GameComp::new(game, settings, None));
+        let screen = runner.screen();
+        let hints = screen.lines().last().unwrap();
+        assert!(hints.contains("F5/Tab = restart | Ctrl+n = new text | Ctrl+g = configuration┘"));
+    }
+
+    #[test]
+    fn paste_is_rejected_in_game() {
+        let dir = TestDir::new("paste_is_rejected_in_game");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf.text_source = TextSource::File(String::new());
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        let text_path = dir.file("text.txt", "pasted text");
+        //path can be pasted into configuration
+        runner.key(KeyCode::Enter).key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Down);
+        runner.send(Message::Paste(format!("{}\n", text_path)));
+        match &runner.model.active_window {
+            WindowType::GameConfigMenu(comp) => assert_eq!(comp.game_conf.text_source, TextSource::File(text_path)),
+            _ => panic!("configuration window should be open"),
+        }
+        runner.key(KeyCode::Enter).send(Message::Paste("pasted text".to_string()));
+        match &runner.model.active_window {
+            WindowType::Game(comp) => {
+                assert!(comp.game.written_vec.is_empty());
+                assert_eq!(comp.game.statistics.pastes, 1);
+            }
+            _ => panic!("game should go on"),
+        }
+    }
+
+    #[test]
+    fn focus_lost_pauses_game() {
+        let dir = TestDir::new("focus_lost_pauses_game");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 80, 24);
+        runner.key(KeyCode::Enter).key(KeyCode::Enter).send(Message::FocusLost);
+        assert!(runner.screen().contains("paused"));
+        runner.type_text("a");
+        match &runner.model.active_window {
+            WindowType::Game(comp) => assert!(!comp.game.is_paused()),
+            _ => panic!("game should go on"),
+        }
+    }
+
+    #[test]
+    fn held_down_keys_make_run_invalid() {
+        let dir = TestDir::new("held_down_keys_make_run_invalid");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf.limit = Limit::WordCount(1);
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        runner.key(KeyCode::Enter).key(KeyCode::Enter).type_text("a");
+        let mut held = KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE);
+        held.kind = KeyEventKind::Repeat;
+        runner.send(Message::KeyInput(held)).type_text(" ");
+        match &runner.model.active_window {
+            WindowType::Statistics(comp) => {
+                assert!(comp.statistics.as_ref().unwrap().cheat_flags.contains(&CheatFlag::KeyRepeat))
+            }
+            _ => panic!("game should be finished"),
+        }
+        let record: RunRecord = serde_json::from_str(
+            fs::read_to_string(&runner.model.settings.paths.history).unwrap().trim(),
+        )
+        .unwrap();
+        assert!(record.cheat_flags.contains(&CheatFlag::KeyRepeat));
+    }
+
+    #[test]
+    fn wide_letters_are_wrapped() {
+        let dir = TestDir::new("wide_letters_are_wrapped");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 40, 12);
+        let conf = GameConf {
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        let text = "我们今天一起学习中文打字你好世界".repeat(3);
+        let game = Game::from_text(conf, &text);
+        runner.model.active_window =
+            WindowType::Game(GameComp::new(game, dir.settings(), None));
+        runner.type_text("我们今");
+        let screen = runner.screen();
+        let rows: Vec<&str> = screen.lines().filter(|row| row.contains('学')).collect();
+        assert!(rows.len() >= 2);
+        //letters take two columns and field border stays in place
+        for row in rows {
+            assert_eq!(row.chars().nth(37), Some('│'));
+        }
+        match &runner.model.active_window {
+            WindowType::Game(comp) => assert_eq!(comp.game.get_correct_words_count(), 2),
+            _ => panic!("game window is expected"),
+        }
+    }
+
+    #[test]
+    fn emulated_layout_is_typed_and_shown() {
+        let dir = TestDir::new("emulated_layout_is_typed_and_shown");
+        let mut settings = dir.settings();
+        settings.input.emulated_layout = Some("colemak".to_string());
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        let conf = GameConf {
+            limit: Limit::None,
+            text_source: TextSource::File(dir.file("text.txt", "rst")),
+            ..GameConf::new()
+        };
+        runner.send(Message::StartGame(conf));
+        assert!(runner.screen().contains("q   w   f   p   g"));
+        //key of the next letter is highlighted
+        let buffer = runner.render();
+        let highlighted: String = buffer
+            .content()
+            .iter()
+            .filter(|cell| cell.bg == ratatui::style::Color::White)
+            .map(|cell| cell.symbol().to_string())
+            .collect();
+        assert_eq!(highlighted.trim(), "r");
+        //qwerty keys in places of colemak letters
+        runner.type_text("sdf");
+        match &runner.model.active_window {
+            WindowType::Statistics(comp) => assert_eq!(comp.game.statistics.wrong_strokes, 0),
+            _ => panic!("game should be finished"),
+        }
+    }
+
+    /// game with some correct and some wrong letters typed
+    fn typed_game(mode: GameMode) -> Game {
+        let conf = GameConf {
+            mode,
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        let mut game = Game::from_text(conf, "the quick brown fox jumps over the lazy dog");
+        for c in "the quack br".chars() {
+            game.char_key_pressed(c);
+        }
+        game
+    }
+
+    fn game_window(mode: GameMode) -> WindowType {
+        let mut comp = GameComp::new(typed_game(mode), Settings::default(), None);
+        //live values depend on time, so they are fixed
+        comp.hud_stats = HudStats {
+            wpm: 42.0,
+            raw_wpm: 50.0,
+            accuracy: 90.0,
+            progress: 0.25,
+        };
+        WindowType::Game(comp)
+    }
+
+    #[test]
+    fn window_snapshots() {
+        assert_window_snapshots("game_normal", game_window(GameMode::Normal));
+        assert_window_snapshots("game_rewrite", game_window(GameMode::Rewrite));
+    }
+}
@@ -1,17 +1,35 @@
+use std::fs;
 use std::time::Duration;
 
 use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::block::{Position, Title};
 
 use num_derive::FromPrimitive;
 use num_traits::{clamp, FromPrimitive};
 
 use super::*;
-use crate::game::{GameConf, GameMode, Limit};
+use crate::config_manager::GameConfPreset;
+use crate::history::RunRecord;
+use crate::game::{GameConf, GameMode, Limit, Strictness, TextSource};
+use crate::keymap::{Action, KeyContext, KeyMap};
 /// component responsible for configuration window
 #[derive(Debug)]
 pub struct GameConfigComp {
     pub game_conf: GameConf,
     pub option: SelectedOption,
+    pub presets: Vec<GameConfPreset>,
+    ///index of selected preset
+    pub preset: Option<usize>,
+    ///name typed for new preset, `None` if name is not being typed
+    pub preset_name_input: Option<String>,
+    ///problem that stops the game from starting
+    pub status: Option<String>,
+    ///best run of every configuration
+    pub personal_bests: Vec<RunRecord>,
+    ///ids and names of language packs
+    pub languages: Vec<(String, String)>,
+    ///bindings shown in hints
+    pub keymap: KeyMap,
 }
 ///configuration option selected for input
 #[derive(Debug, PartialEq, FromPrimitive, Clone, Copy)]
@@ -19,6 +37,10 @@ pub enum SelectedOption {
     Mode,
     Limit,
     Input,
+    TextSource,
+    Language,
+    Strictness,
+    Preset,
 }
 impl SelectedOption {
     ///select next option
@@ -34,7 +56,7 @@ impl SelectedOption {
         let i = *self as i32 - 1;
         *self = match FromPrimitive::from_i32(i) {
             Some(opt) => opt,
-            None => SelectedOption::Input,
+            None => SelectedOption::Preset,
         }
     }
     ///select next type of this option
@@ -53,7 +75,9 @@ impl SelectedOption {
                     Limit::None => Limit::Time(Duration::from_secs(30)),
                 };
             }
-            SelectedOption::Input => (),
+            SelectedOption::TextSource => toggle_text_source(&mut conf.text_source),
+            SelectedOption::Strictness => toggle_strictness(&mut conf.strictness),
+            SelectedOption::Input | SelectedOption::Language | SelectedOption::Preset => (),
         }
     }
 
@@ -73,10 +97,114 @@ impl SelectedOption {
                     Limit::None => Limit::WordCount(50),
                 };
             }
-            SelectedOption::Input => (),
+            SelectedOption::TextSource => toggle_text_source(&mut conf.text_source),
+            SelectedOption::Strictness => toggle_strictness(&mut conf.strictness),
+            SelectedOption::Input | SelectedOption::Language | SelectedOption::Preset => (),
         }
     }
 }
+fn toggle_text_source(source: &mut TextSource) {
+    *source = match source {
+        TextSource::Generated => TextSource::File(String::new()),
+        TextSource::File(_) => TextSource::Generated,
+    }
+}
+fn toggle_strictness(strictness: &mut Strictness) {
+    *strictness = match strictness {
+        Strictness::Normal => Strictness::Strict,
+        Strictness::Strict => Strictness::Normal,
+    }
+}
+
+impl GameConfigComp {
+    pub fn new(
+        game_conf: GameConf,
+        presets: Vec<GameConfPreset>,
+        personal_bests: Vec<RunRecord>,
+        languages: Vec<(String, String)>,
+        keymap: KeyMap,
+    ) -> GameConfigComp {
+        GameConfigComp {
+            personal_bests,
+            languages,
+            keymap,
+            preset: presets.iter().position(|p| p.game_conf == game_conf),
+            game_conf,
+            option: SelectedOption::Mode,
+            presets,
+            preset_name_input: None,
+            status: None,
+        }
+    }
+    ///select next or previous preset and use its configuration
+    fn select_preset(&mut self, forward: bool) {
+        if self.presets.is_empty() {
+            return;
+        }
+        let len = self.presets.len();
+        let i = match (self.preset, forward) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.preset = Some(i);
+        self.game_conf = self.presets[i].game_conf.clone();
+    }
+    ///select next or previous language pack
+    fn select_language(&mut self, forward: bool) {
+        if self.languages.is_empty() {
+            return;
+        }
+        let len = self.languages.len();
+        let i = match (self.languages.iter().position(|(id, _)| *id == self.game_conf.language), forward) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, _) => 0,
+        };
+        self.game_conf.language = self.languages[i].0.clone();
+    }
+    ///name of language pack used for generated text
+    fn language_name(&self) -> String {
+        match self.languages.iter().find(|(id, _)| *id == self.game_conf.language) {
+            Some((_, name)) => name.clone(),
+            None => format!("{} (not installed)", self.game_conf.language),
+        }
+    }
+    ///saves current configuration as preset, preset with the same name is replaced
+    fn save_preset(&mut self, name: String) -> Option<Message> {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            self.status = Some("Preset name can't be empty".to_string());
+            return None;
+        }
+        let preset = GameConfPreset {
+            name: name.clone(),
+            game_conf: self.game_conf.clone(),
+        };
+        match self.presets.iter().position(|p| p.name == name) {
+            Some(i) => {
+                self.presets[i] = preset;
+                self.preset = Some(i);
+            }
+            None => {
+                self.presets.push(preset);
+                self.preset = Some(self.presets.len() - 1);
+            }
+        }
+        self.status = None;
+        Some(Message::PresetsChanged(self.presets.clone()))
+    }
+    ///checks if game can be started with current configuration
+    fn validate(&self) -> Result<(), String> {
+        if let TextSource::File(path) = &self.game_conf.text_source {
+            if !fs::metadata(path).is_ok_and(|m| m.is_file()) {
+                return Err(format!("file \"{}\" doesn't exist", path));
+            }
+        }
+        Ok(())
+    }
+}
 
 #[allow(unused_variables)]
 #[allow(dead_code)]
@@ -85,25 +213,79 @@ impl Component for GameConfigComp {
     fn handle_message(&mut self, msg: Message) -> Message {
         // let mut conf = &mut self.game_conf;
         let answer = match msg {
-            Message::KeyInput(key) => match key.code {
-                KeyCode::Esc => Some(Message::GoToWindow(WindowType::Menu(MenuComp::new()))),
-                KeyCode::Down => {
+            //typing preset name
+            Message::Action(action) if self.preset_name_input.is_some() => match action {
+                Action::Back => {
+                    self.preset_name_input = None;
+                    None
+                }
+                Action::Confirm => {
+                    let name = self.preset_name_input.take().unwrap();
+                    self.save_preset(name)
+                }
+                Action::DeleteChar => {
+                    self.preset_name_input.as_mut().unwrap().pop();
+                    None
+                }
+                _ => None,
+            },
+            Message::KeyInput(key) if self.preset_name_input.is_some() => {
+                if let Some(c) = typed_char(&key) {
+                    self.preset_name_input.as_mut().unwrap().push(c);
+                }
+                None
+            }
+            Message::Paste(ref text) if self.preset_name_input.is_some() => {
+                self.preset_name_input.as_mut().unwrap().push_str(&pasted_line(text));
+                None
+            }
+            Message::Paste(ref text) if self.option == SelectedOption::TextSource => {
+                if let TextSource::File(path) = &mut self.game_conf.text_source {
+                    path.push_str(&pasted_line(text));
+                }
+                None
+            }
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::OpenMenu),
+                Action::Down => {
                     self.option.next();
                     None
                 }
-                KeyCode::Up => {
+                Action::Up => {
                     self.option.prev();
                     None
                 }
-                KeyCode::Left => {
+                Action::Left if self.option == SelectedOption::Preset => {
+                    self.select_preset(false);
+                    None
+                }
+                Action::Right if self.option == SelectedOption::Preset => {
+                    self.select_preset(true);
+                    None
+                }
+                Action::Left if self.option == SelectedOption::Language => {
+                    self.select_language(false);
+                    None
+                }
+                Action::Right if self.option == SelectedOption::Language => {
+                    self.select_language(true);
+                    None
+                }
+                Action::Left => {
                     self.option.left(&mut self.game_conf);
                     None
                 }
-                KeyCode::Right => {
+                Action::Right => {
                     self.option.right(&mut self.game_conf);
                     None
                 }
-                KeyCode::Backspace => {
+                Action::DeleteChar if self.option == SelectedOption::TextSource => {
+                    if let TextSource::File(path) = &mut self.game_conf.text_source {
+                        path.pop();
+                    }
+                    None
+                }
+                Action::DeleteChar => {
                     match &mut self.game_conf.limit {
                         Limit::Time(t) => {
                             *t = Duration::from_secs(clamp(t.as_secs() / 10, 0, 600));
@@ -113,10 +295,42 @@ impl Component for GameConfigComp {
                             *wc = clamp(*wc / 10,0,10000);
                             None
                         }
-                        Limit::None => None, //TODO: path for custom file
+                        Limit::None => None,
+                    }
+                }
+                Action::Save if self.option == SelectedOption::Preset => {
+                    self.preset_name_input = Some(
+                        self.preset
+                            .map(|i| self.presets[i].name.clone())
+                            .unwrap_or_default(),
+                    );
+                    None
+                }
+                Action::Remove if self.option == SelectedOption::Preset => {
+                    if let Some(i) = self.preset.take() {
+                        self.presets.remove(i);
+                        Some(Message::PresetsChanged(self.presets.clone()))
+                    } else {
+                        None
                     }
                 }
-                KeyCode::Char(c) if self.option == SelectedOption::Input => {
+                Action::Confirm => match self.validate() {
+                    Ok(()) => Some(Message::StartGame(self.game_conf.clone())),
+                    Err(e) => {
+                        self.status = Some(e);
+                        None
+                    }
+                },
+                _ => None,
+            },
+            Message::KeyInput(key) => match typed_char(&key) {
+                Some(c) if self.option == SelectedOption::TextSource => {
+                    if let TextSource::File(path) = &mut self.game_conf.text_source {
+                        path.push(c);
+                    }
+                    None
+                }
+                Some(c) if self.option == SelectedOption::Input => {
                     if c.is_numeric(){
                         match &mut self.game_conf.limit {
                             Limit::Time(t) => {
@@ -130,10 +344,10 @@ impl Component for GameConfigComp {
                             Limit::WordCount(wc) => {
                                 //10000 is max word count, crunch to avoid too big numbers
                                 let mut count = *wc * 10;
-                                count += c.to_digit(10).unwrap() as u32;
+                                count += c.to_digit(10).unwrap();
                                 *wc = clamp(count, 1,10000);
                             },
-                            Limit::None => todo!(),
+                            Limit::None => (),
                         }
                         None
                     }else{
@@ -169,9 +383,6 @@ impl Component for GameConfigComp {
                     //     Limit::None => None, //TODO: path for custom file
                     // }
                 }
-                KeyCode::Enter | KeyCode::Char(' ') => {
-                    Some(Message::StartGame(self.game_conf.clone()))
-                }
                 _ => None,
             },
             _ => None,
@@ -181,28 +392,65 @@ impl Component for GameConfigComp {
             None => msg,
         }
     }
+
+    fn key_context(&self) -> KeyContext {
+        let typing_path = self.option == SelectedOption::TextSource
+            && matches!(self.game_conf.text_source, TextSource::File(_));
+        if self.preset_name_input.is_some() || typing_path {
+            KeyContext::Typing
+        } else {
+            KeyContext::Navigation
+        }
+    }
+    fn min_size(&self) -> (u16, u16) {
+        (50, 23)
+    }
     /// render game configuration window
     fn view(&mut self, f: &mut Frame) {
         //Rendering border
-        f.render_widget(Block::new().title("Game configuration | Esc = go to menu | arrow buttons = navigation | Enter or Space = start the game").borders(Borders::ALL), f.size());
+        let best_text = match self.personal_bests.iter().find(|r| r.game_conf == self.game_conf) {
+            Some(best) => format!(" personal best: {} WPM, {}% accuracy ", best.wpm.round(), best.accuracy.round()),
+            None => " no personal best yet ".to_string(),
+        };
+        f.render_widget(
+            Block::new()
+                .title(format!(
+                    "Game configuration | {}",
+                    self.keymap.hints(
+                        KeyContext::Navigation,
+                        &[
+                            (&[Action::Back], "go to menu"),
+                            (&[Action::Up, Action::Down, Action::Left, Action::Right], "navigation"),
+                            (&[Action::Confirm], "start the game"),
+                        ],
+                    )
+                ))
+                .title(Title::from(best_text).position(Position::Bottom).alignment(Alignment::Center))
+                .borders(Borders::ALL),
+            f.size(),
+        );
 
         // +--------------------------------+
         // |  rewrite  normal               |
-        // |  time  words  text             |
-        // |  limit:{time|count|text_path}  |
+        // |  time  words  whole text       |
+        // |  limit:{time|count}            |
+        // |  generated  file:{path}        |
+        // |  language:{name}               |
+        // |  normal  strict                |
+        // |  preset:{name}                 |
         // +--------------------------------+
         let content_layout = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
-            .constraints(Constraint::from_lengths([1, f.size().height - 2, 1]))
+            .constraints(Constraint::from_lengths([1, f.size().height.saturating_sub(2), 1]))
             .split(f.size()); //top and bottom margins
         let content_layout = Layout::default()
             .direction(ratatui::layout::Direction::Horizontal)
-            .constraints(Constraint::from_lengths([1, f.size().width - 2, 1]))
+            .constraints(Constraint::from_lengths([1, f.size().width.saturating_sub(2), 1]))
             .split(content_layout[1]); //left and right margins
 
         let selectors_layout = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
-            .constraints(Constraint::from_ratios([(1, 3), (1, 3), (1, 3)]))
+            .constraints(Constraint::from_ratios([(1, 7), (1, 7), (1, 7), (1, 7), (1, 7), (1, 7), (1, 7)]))
             .split(content_layout[1]);
 
         let render =
@@ -210,7 +458,7 @@ impl Component for GameConfigComp {
                  if is_selected {
                     f.render_widget(Block::new().borders(Borders::ALL).style(Style::new().green()), *rect);
                 } 
-                let box_top_padding = (rect.height as f32 / 2 as f32).round() as u16 - 1;
+                let box_top_padding = ((rect.height as f32 / 2_f32).round() as u16).saturating_sub(1);
                 let rect = Layout::default()
                     .direction(ratatui::layout::Direction::Vertical)
                     .constraints(Constraint::from_lengths([box_top_padding, 1]))
@@ -235,6 +483,48 @@ impl Component for GameConfigComp {
             .split(selectors_layout[2]);
         let input_text = format!("limit:{:?}", &self.game_conf.limit);
 
+        let text_source_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints(Constraint::from_ratios([(1, 2), (1, 2)]))
+            .split(selectors_layout[3]);
+        let file_text = match &self.game_conf.text_source {
+            TextSource::File(path) => format!("file:{}", path),
+            TextSource::Generated => "file".to_string(),
+        };
+
+        let language_text = format!("language:< {} >", self.language_name());
+
+        let strictness_layout = Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints(Constraint::from_ratios([(1, 2), (1, 2)]))
+            .split(selectors_layout[5]);
+
+        let preset_text = if let Some(name) = &self.preset_name_input {
+            format!(
+                "save preset as:{}_ | {}",
+                name,
+                self.keymap.hints(KeyContext::Typing, &[(&[Action::Confirm], "save"), (&[Action::Back], "cancel")])
+            )
+        } else if let Some(status) = &self.status {
+            status.clone()
+        } else {
+            match self.preset {
+                Some(i) => format!(
+                    "preset:{}{} ({}/{}) | {}",
+                    self.presets[i].name,
+                    if self.presets[i].game_conf == self.game_conf { "" } else { "*" },
+                    i + 1,
+                    self.presets.len(),
+                    self.keymap.hints(KeyContext::Navigation, &[(&[Action::Save], "save as"), (&[Action::Remove], "remove")])
+                ),
+                None => format!(
+                    "preset:none ({} saved) | {}",
+                    self.presets.len(),
+                    self.keymap.hints(KeyContext::Navigation, &[(&[Action::Save], "save current configuration")])
+                ),
+            }
+        };
+
         {
             render(
                 "normal",
@@ -254,31 +544,40 @@ impl Component for GameConfigComp {
                 &limit_selector_layout[1],
                 false,f            );
             render(
-                "custom text",
+                "whole text",
                 &limit_selector_layout[2],
                 false,f            );
             render(
                 input_text.as_str(),
                 &limit_input_layout[0],
                 false,f            );
+            render(
+                "generated text",
+                &text_source_layout[0],
+                false,f            );
+            render(
+                file_text.as_str(),
+                &text_source_layout[1],
+                false,f            );
+            render(
+                language_text.as_str(),
+                &selectors_layout[4],
+                false,f            );
+            render(
+                "normal",
+                &strictness_layout[0],
+                false,f            );
+            render(
+                "strict (fix mistakes to move on)",
+                &strictness_layout[1],
+                false,f            );
+            render(
+                preset_text.as_str(),
+                &selectors_layout[6],
+                false,f            );
         }
 
-
-
-        match &self.option {
-            SelectedOption::Mode => {
-                f.render_widget(Block::new().borders(Borders::ALL).style(Style::new().white()), selectors_layout[0]);
-
-            },
-            SelectedOption::Limit => {
-                f.render_widget(Block::new().borders(Borders::ALL).style(Style::new().white()), selectors_layout[1]);
-
-            },
-            SelectedOption::Input => {
-                f.render_widget(Block::new().borders(Borders::ALL).style(Style::new().white()), selectors_layout[2]);
-
-            }
-        }
+        f.render_widget(Block::new().borders(Borders::ALL).style(Style::new().white()), selectors_layout[self.option as usize]);
 
         //rendering set settings for limits
         match self.game_conf.limit {
@@ -293,7 +592,7 @@ impl Component for GameConfigComp {
                 true,f            
             ),
             Limit::None => render(
-                "custom text",
+                "whole text",
                 &limit_selector_layout[2],
                 true,f            
             ),
@@ -311,5 +610,70 @@ impl Component for GameConfigComp {
                 true,f            
             ),
         }
+        //rendering set text source
+        match self.game_conf.text_source {
+            TextSource::Generated => render(
+                "generated text",
+                &text_source_layout[0],
+                true,f
+            ),
+            TextSource::File(_) => render(
+                file_text.as_str(),
+                &text_source_layout[1],
+                true,f
+            ),
+        }
+        //rendering set strictness
+        match self.game_conf.strictness {
+            Strictness::Normal => render(
+                "normal",
+                &strictness_layout[0],
+                true,f
+            ),
+            Strictness::Strict => render(
+                "strict (fix mistakes to move on)",
+                &strictness_layout[1],
+                true,f
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::model::Model;
+    use crate::language::Languages;
+
+    #[test]
+    fn language_is_chosen_in_configuration() {
+        let dir = TestDir::new("language_is_chosen_in_configuration");
+        dir.file("languages/eo/corpus.txt", "bb bb bb bb");
+        dir.file("languages/eo/pack.toml", "name = \"Esperanto\"");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf.limit = Limit::WordCount(2);
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        runner.key(KeyCode::Enter);
+        for _ in 0..4 {
+            runner.key(KeyCode::Down);
+        }
+        assert!(runner.screen().contains("language:< en >"));
+        //user pack comes after built-in ones
+        runner.key(KeyCode::Left);
+        assert!(runner.screen().contains("language:< Esperanto >"));
+        runner.key(KeyCode::Enter);
+        assert!(runner.screen().contains("bb bb"));
+        match &runner.model.active_window {
+            WindowType::Game(comp) => assert_eq!(comp.game.game_conf.language, "eo"),
+            _ => panic!("game should be started"),
+        }
+    }
+
+    #[test]
+    fn window_snapshots() {
+        let languages = Languages::new("").names();
+        let comp = GameConfigComp::new(GameConf::new(), Vec::new(), Vec::new(), languages, KeyMap::default());
+        assert_window_snapshots("game_config", WindowType::GameConfigMenu(comp));
     }
 }
@@ -0,0 +1,157 @@
+//! On-screen keyboard drawn under the typed text
+
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+
+use super::*;
+use crate::layout::{Finger, Key, KeyPos, KeyboardLayout, SPACE_ROW};
+
+/// rows of keyboard with number row and space bar
+pub const KEYBOARD_HEIGHT: u16 = 5;
+/// columns taken by one key with the gap after it, from the biggest keyboard to the smallest
+const KEY_WIDTHS: [usize; 3] = [4, 3, 2];
+/// quarters of key every row is moved to the right, like rows of real keyboard
+const ROW_OFFSETS: [usize; 4] = [0, 2, 3, 1];
+/// keys of bottom row above the ends of space bar
+const SPACE_FROM: usize = 3;
+const SPACE_TO: usize = 7;
+
+/// colour of keys pressed by the finger, both hands use the same colours
+fn finger_color(finger: Finger) -> Color {
+    match finger {
+        Finger::LeftPinky | Finger::RightPinky => Color::Magenta,
+        Finger::LeftRing | Finger::RightRing => Color::Blue,
+        Finger::LeftMiddle | Finger::RightMiddle => Color::Cyan,
+        Finger::LeftIndex | Finger::RightIndex => Color::Green,
+        Finger::Thumb => Color::Gray,
+    }
+}
+
+/// columns taken by keyboard of the layout with keys of given width
+fn keyboard_width(layout: &KeyboardLayout, key_width: usize) -> u16 {
+    layout
+        .rows
+        .iter()
+        .zip(ROW_OFFSETS)
+        .map(|(row, offset)| offset * key_width / 4 + row.chars().count() * key_width)
+        .max()
+        .unwrap_or(0)
+        .saturating_sub(1) as u16
+}
+
+/// draws keyboard of the biggest size that fits the area, keys are coloured by finger,
+/// key of `next` char is highlighted and key of `mistake` is red,
+/// number row is left out in low area and nothing is drawn if even the smallest keyboard doesn't fit
+pub fn keyboard_view(f: &mut Frame, area: Rect, layout: &KeyboardLayout, next: Option<char>, mistake: Option<char>) {
+    let key_width = match KEY_WIDTHS.iter().find(|w| keyboard_width(layout, **w) <= area.width) {
+        Some(w) => *w,
+        None => return,
+    };
+    let first_row = match area.height {
+        h if h >= KEYBOARD_HEIGHT => 0,
+        h if h == KEYBOARD_HEIGHT - 1 => 1,
+        _ => return,
+    };
+    let next_key = next.and_then(|c| layout.key(c));
+    let mistake_key = mistake.and_then(|c| layout.key(c));
+    //keys show what they type with shift when next char needs it
+    let shift = next_key.is_some_and(|key| key.shift);
+    let key_style = |pos: KeyPos| {
+        if mistake_key.is_some_and(|key| key.pos == pos) {
+            Style::new().white().on_red().bold()
+        } else if next_key.is_some_and(|key| key.pos == pos) {
+            Style::new().black().on_white().bold()
+        } else {
+            Style::new().black().bg(finger_color(pos.finger()))
+        }
+    };
+
+    let mut lines: Vec<Line> = Vec::new();
+    for (row, offset) in ROW_OFFSETS.iter().enumerate().skip(first_row) {
+        let mut spans = vec![Span::raw(" ".repeat(offset * key_width / 4))];
+        let count = layout.rows[row].chars().count();
+        for col in 0..count {
+            let pos = KeyPos { row, col };
+            let label = match layout.char_at(Key { pos, shift }) {
+                Some(c) => c.to_string(),
+                None => String::new(),
+            };
+            let cell = format!("{:^1$}", label, key_width - 1);
+            if label.is_empty() {
+                spans.push(Span::raw(cell)); //no key in this place
+            } else {
+                spans.push(Span::styled(cell, key_style(pos)));
+            }
+            spans.push(Span::raw(" "));
+        }
+        lines.push(Line::from(spans));
+    }
+    let space_start = ROW_OFFSETS[3] * key_width / 4 + SPACE_FROM * key_width;
+    let space_width = (SPACE_TO - SPACE_FROM + 1) * key_width - 1;
+    lines.push(Line::from(vec![
+        Span::raw(" ".repeat(space_start)),
+        Span::styled(format!("{:^1$}", "space", space_width), key_style(KeyPos { row: SPACE_ROW, col: 0 })),
+    ]));
+
+    let width = keyboard_width(layout, key_width);
+    let height = lines.len() as u16;
+    let x = area.x + (area.width - width) / 2;
+    f.render_widget(
+        Paragraph::new(lines).style(Style::new().white().on_black()),
+        Rect::new(x, area.y, width, height),
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    use crate::game::Limit;
+    use crate::headless::{HeadlessRunner, TestDir};
+    use crate::model::Model;
+
+    #[test]
+    fn keyboard_shows_fingers_and_mistakes() {
+        let dir = TestDir::new("keyboard_shows_fingers_and_mistakes");
+        let mut settings = dir.settings();
+        settings.hud.show_keyboard = true;
+        settings.gameplay.game_conf.limit = Limit::None;
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings.clone()), 80, 24);
+        runner.send(Message::StartGame(settings.gameplay.game_conf));
+        let key_color = |runner: &mut HeadlessRunner, key: &str| {
+            let buffer = runner.render();
+            let area = buffer.area;
+            //keyboard is under the text, borders have key hints
+            (1..area.height - 1)
+                .rev()
+                .flat_map(|y| (0..area.width).map(move |x| (x, y)))
+                .map(|(x, y)| buffer.get(x, y))
+                .find(|cell| cell.symbol() == key)
+                .map(|cell| cell.bg)
+        };
+        assert_eq!(key_color(&mut runner, "f"), Some(Color::Green));
+        assert_eq!(key_color(&mut runner, "q"), Some(Color::Magenta));
+        //next letter
+        let highlighted: String = runner
+            .render()
+            .content()
+            .iter()
+            .filter(|cell| cell.bg == Color::White)
+            .map(|cell| cell.symbol().to_string())
+            .collect();
+        assert_eq!(highlighted.trim(), "a");
+        runner.type_text("x");
+        assert_eq!(key_color(&mut runner, "x"), Some(Color::Red));
+        match &runner.model.active_window {
+            WindowType::Game(comp) => assert!(comp.next_tick().unwrap() <= SystemTime::now() + Duration::from_millis(300)),
+            _ => panic!("game window is expected"),
+        }
+
+        //smaller keys in narrow terminal
+        runner.resize(40, 20);
+        let screen = runner.screen();
+        assert!(screen.contains("q w e r t y u i o p"));
+        assert!(screen.contains("space"));
+    }
+}
@@ -1,7 +1,5 @@
-use crate::component::game_conf_component;
-use crate::game;
-
 use super::*;
+use crate::keymap::{Action, KeyContext, KeyMap};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use ratatui::{self, widgets::Padding};
@@ -9,13 +7,20 @@ use ratatui::{self, widgets::Padding};
 #[derive(Debug)]
 pub struct MenuComp {
     current_opt: MenuOptions,
+    ///message shown under options
+    pub status: Option<String>,
+    ///bindings shown in window title
+    pub keymap: KeyMap,
 }
 
 ///options in menu navigational panel
 #[derive(Debug, PartialEq, FromPrimitive, Clone, Copy)]
 pub enum MenuOptions {
     GameConf = 0,
-    ExitProgram = 1,
+    Progress = 1,
+    Profiles = 2,
+    Settings = 3,
+    ExitProgram = 4,
 }
 impl MenuOptions {
     ///next option
@@ -36,31 +41,31 @@ impl MenuOptions {
     }
 }
 impl MenuComp {
-    pub fn new() -> MenuComp {
+    pub fn new(keymap: KeyMap) -> MenuComp {
         MenuComp {
             current_opt: MenuOptions::GameConf,
+            status: None,
+            keymap,
         }
     }
 }
 impl Component for MenuComp {
     fn handle_message(&mut self, msg: Message) -> Message {
         let answer = match msg {
-            Message::KeyInput(key) => match key.code {
-                // KeyCode::Esc => Some(Message::Quit),
-                KeyCode::Char(' ') | KeyCode::Enter => match self.current_opt {
-                    MenuOptions::GameConf => Some(Message::GoToWindow(WindowType::GameConfigMenu(
-                        GameConfigComp {
-                            game_conf: game::GameConf::new(),
-                            option: game_conf_component::SelectedOption::Mode,
-                        },
-                    ))),
+            Message::Action(action) => match action {
+                // Action::Back => Some(Message::Quit),
+                Action::Confirm => match self.current_opt {
+                    MenuOptions::GameConf => Some(Message::OpenGameConfig),
+                    MenuOptions::Progress => Some(Message::OpenProgress),
+                    MenuOptions::Profiles => Some(Message::OpenProfiles),
+                    MenuOptions::Settings => Some(Message::OpenSettings),
                     MenuOptions::ExitProgram => Some(Message::Quit),
                 },
-                KeyCode::Down => {
+                Action::Down => {
                     self.current_opt.go_next();
                     None
                 }
-                KeyCode::Up => {
+                Action::Up => {
                     self.current_opt.go_prev();
                     None
                 }
@@ -74,8 +79,12 @@ impl Component for MenuComp {
         }
     }
 
+    fn min_size(&self) -> (u16, u16) {
+        (30, 13)
+    }
+
     fn view(&mut self, f: &mut Frame) {
-        let lines = vec![
+        let mut lines = vec![
             Line::from(Span::styled(
                 "Start game",
                 if self.current_opt == MenuOptions::GameConf {
@@ -84,6 +93,30 @@ impl Component for MenuComp {
                     Style::new()
                 },
             )),
+            Line::from(Span::styled(
+                "Progress",
+                if self.current_opt == MenuOptions::Progress {
+                    Style::new().black().on_white()
+                } else {
+                    Style::new()
+                },
+            )),
+            Line::from(Span::styled(
+                "Profiles",
+                if self.current_opt == MenuOptions::Profiles {
+                    Style::new().black().on_white()
+                } else {
+                    Style::new()
+                },
+            )),
+            Line::from(Span::styled(
+                "Settings",
+                if self.current_opt == MenuOptions::Settings {
+                    Style::new().black().on_white()
+                } else {
+                    Style::new()
+                },
+            )),
             Line::from(Span::styled(
                 "Exit program",
                 if self.current_opt == MenuOptions::ExitProgram {
@@ -93,11 +126,19 @@ impl Component for MenuComp {
                 },
             )),
         ];
+        if let Some(status) = &self.status {
+            lines.push(Line::from(""));
+            lines.extend(status.lines().map(Line::from));
+        }
+        let hints = self.keymap.hints(
+            KeyContext::Navigation,
+            &[(&[Action::Up, Action::Down], "navigation"), (&[Action::Confirm], "select option")],
+        );
         f.render_widget(
             Paragraph::new(lines)
                 .block(
                     Block::new()
-                        .title("Menu | arrow buttons = navigation | Space or Enter = Select option")
+                        .title(format!("Menu | {}", hints))
                         .borders(Borders::ALL)
                         .padding(Padding::vertical(3)),
                 )
@@ -109,3 +150,38 @@ impl Component for MenuComp {
         );
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::model::Model;
+
+    #[test]
+    fn menu_screen() {
+        let dir = TestDir::new("menu_screen");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 40, 13);
+        runner.key(KeyCode::Down).key(KeyCode::Down);
+        let screen = runner.screen();
+        assert!(screen.contains("Start game"));
+        assert!(screen.contains("Progress"));
+        assert!(screen.contains("Profiles"));
+        assert!(screen.contains("Settings"));
+        assert!(screen.contains("Exit program"));
+        let buffer = runner.render();
+        //selected option is highlighted
+        let highlighted: String = (0..40)
+            .map(|x| buffer.get(x, 6))
+            .filter(|cell| cell.bg == ratatui::style::Color::White)
+            .map(|cell| cell.symbol().to_string())
+            .collect();
+        assert_eq!(highlighted, "Profiles");
+        runner.resize(60, 14);
+        assert_eq!(runner.screen().lines().next().unwrap().chars().count(), 60);
+    }
+
+    #[test]
+    fn window_snapshots() {
+        assert_window_snapshots("menu", WindowType::Menu(MenuComp::new(KeyMap::default())));
+    }
+}
@@ -1,23 +1,70 @@
+use std::time::SystemTime;
+
 use crate::game::{FieldState, Game};
-use crossterm::event::KeyCode;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::Alignment;
 use ratatui::prelude::{Line, Span, Style};
 use ratatui::style::Stylize;
 use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
 use ratatui::Frame;
 
+use crate::keymap::KeyContext;
 use crate::Message;
 /// component represents window, its behavior and rendering
 pub trait Component {
     /// processes message, reacts to it and answers with another message
     fn handle_message(&mut self, msg: Message) -> Message;
 
+    /// tells which key bindings should be used for this component
+    fn key_context(&self) -> KeyContext {
+        KeyContext::Navigation
+    }
+
+    /// smallest frame (width, height) component can be rendered in
+    fn min_size(&self) -> (u16, u16) {
+        (30, 10)
+    }
+
+    /// time when component needs `Tick` to update, `None` if it changes only on input
+    fn next_tick(&self) -> Option<SystemTime> {
+        None
+    }
+
     /// renders component in set frame
     fn view(&mut self, f: &mut Frame);
 }
+
+/// char typed into text field, `None` for keys that don't type or are pressed with Ctrl, Alt or other modifiers
+fn typed_char(key: &KeyEvent) -> Option<char> {
+    match key.code {
+        KeyCode::Char(c) if key.modifiers.difference(KeyModifiers::SHIFT).is_empty() => Some(c),
+        _ => None,
+    }
+}
+
+/// pasted text that can be put into one line field
+fn pasted_line(text: &str) -> String {
+    text.chars().filter(|c| !c.is_control()).collect()
+}
+
+/// renders message asking to make terminal bigger
+fn too_small_view(f: &mut Frame, (width, height): (u16, u16)) {
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from("Terminal is too small"),
+            Line::from(format!("need {}x{}, now {}x{}", width, height, f.size().width, f.size().height)),
+        ])
+        .style(Style::new().white().on_black())
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: true }),
+        f.size(),
+    );
+}
 pub mod menu_component;
 pub use menu_component::MenuComp;
 
+pub mod keyboard;
+
 pub mod game_component;
 pub use game_component::GameComp;
 
@@ -27,23 +74,50 @@ pub use statistic_component::StatComp;
 pub mod game_conf_component;
 pub use game_conf_component::GameConfigComp;
 
+pub mod settings_component;
+pub use settings_component::SettingsComp;
+
+pub mod progress_component;
+pub use progress_component::ProgressComp;
+
+pub mod profile_component;
+pub use profile_component::ProfileComp;
+
 /// enum representing witch window is active
 #[allow(dead_code)]
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 pub enum WindowType {
     Menu(MenuComp),
     Game(GameComp),
     Statistics(StatComp),
     GameConfigMenu(GameConfigComp),
+    Settings(SettingsComp),
+    Progress(ProgressComp),
+    Profiles(ProfileComp),
 }
 impl WindowType {
     // gets itself as a component - crunch
+    fn get_as_comp_ref(&self) -> &dyn Component {
+        match self {
+            WindowType::Menu(comp) => comp,
+            WindowType::Game(comp) => comp,
+            WindowType::Statistics(comp) => comp,
+            WindowType::GameConfigMenu(comp) => comp,
+            WindowType::Settings(comp) => comp,
+            WindowType::Progress(comp) => comp,
+            WindowType::Profiles(comp) => comp,
+        }
+    }
     fn get_as_comp(&mut self) -> &mut dyn Component {
         match self {
             WindowType::Menu(comp) => comp,
             WindowType::Game(comp) => comp,
             WindowType::Statistics(comp) => comp,
             WindowType::GameConfigMenu(comp) => comp,
+            WindowType::Settings(comp) => comp,
+            WindowType::Progress(comp) => comp,
+            WindowType::Profiles(comp) => comp,
         }
     }
 }
@@ -52,7 +126,53 @@ impl Component for WindowType {
         self.get_as_comp().handle_message(msg)
     }
 
+    fn key_context(&self) -> KeyContext {
+        self.get_as_comp_ref().key_context()
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        self.get_as_comp_ref().min_size()
+    }
+
+    fn next_tick(&self) -> Option<SystemTime> {
+        self.get_as_comp_ref().next_tick()
+    }
+
     fn view(&mut self, f: &mut Frame) {
-        self.get_as_comp().view(f)
+        let (width, height) = self.min_size();
+        if f.size().width < width || f.size().height < height {
+            too_small_view(f, (width, height));
+        } else {
+            self.get_as_comp().view(f)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{HeadlessRunner, TestDir};
+    use crate::model::Model;
+
+    #[test]
+    fn too_small_terminal_shows_message() {
+        let dir = TestDir::new("too_small_terminal_shows_message");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 25, 8);
+        let screen = runner.screen();
+        assert!(screen.contains("Terminal is too small"));
+        assert!(screen.contains("need 30x13, now 25x8"));
+    }
+
+    #[test]
+    fn text_fields_ignore_modifier_combos() {
+        let dir = TestDir::new("text_fields_ignore_modifier_combos");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 80, 24);
+        runner.key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter);
+        runner.key(KeyCode::Char('a')).type_text("an");
+        runner.key_with(KeyCode::Char('x'), KeyModifiers::ALT);
+        runner.key_with(KeyCode::Char('u'), KeyModifiers::CONTROL);
+        runner.key_with(KeyCode::Char('N'), KeyModifiers::SHIFT);
+        runner.key(KeyCode::Enter);
+        assert!(runner.screen().contains("Profile anN is created"));
     }
 }
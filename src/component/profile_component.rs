@@ -0,0 +1,289 @@
+use std::path::PathBuf;
+
+use ratatui::layout::{Constraint, Layout};
+use ratatui::widgets::Padding;
+
+use super::*;
+use crate::keymap::{Action, KeyContext, KeyMap};
+use crate::profile::{self, Profile};
+
+/// change of profile list that waits for user
+#[derive(Debug, PartialEq)]
+pub enum ProfileEdit {
+    ///name typed for new profile
+    Create(String),
+    ///new name typed for selected profile
+    Rename(String),
+    ///selected profile is removed after confirmation
+    Delete,
+}
+
+/// component responsible for window with profile list
+#[derive(Debug)]
+pub struct ProfileComp {
+    ///folder with all profiles
+    pub root: PathBuf,
+    pub names: Vec<String>,
+    ///index of selected profile
+    pub selected: usize,
+    ///name of profile in use
+    pub current: String,
+    pub edit: Option<ProfileEdit>,
+    ///result of last change
+    pub status: Option<String>,
+    ///bindings shown in hints
+    pub keymap: KeyMap,
+}
+impl ProfileComp {
+    pub fn new(current: &Profile, keymap: KeyMap) -> ProfileComp {
+        let mut comp = ProfileComp {
+            root: current.root.clone(),
+            names: Vec::new(),
+            selected: 0,
+            current: current.name.clone(),
+            edit: None,
+            status: None,
+            keymap,
+        };
+        comp.refresh(&current.name);
+        comp
+    }
+
+    /// reads profile list again and selects profile with the name
+    pub fn refresh(&mut self, name: &str) {
+        self.names = profile::list(&self.root);
+        self.selected = self.names.iter().position(|n| n == name).unwrap_or(0);
+    }
+
+    fn selected_name(&self) -> Option<String> {
+        self.names.get(self.selected).cloned()
+    }
+
+    /// finishes edit, returns message if model has to change
+    fn confirm_edit(&mut self) -> Option<Message> {
+        let selected = self.selected_name()?;
+        match self.edit.take()? {
+            ProfileEdit::Create(name) => {
+                match profile::create(&self.root, name.trim()) {
+                    Ok(created) => {
+                        self.refresh(&created.name);
+                        self.status = Some(format!("Profile {} is created", created.name));
+                    }
+                    Err(e) => self.status = Some(e),
+                }
+                None
+            }
+            //files of profile in use are open, so model renames it
+            ProfileEdit::Rename(name) => Some(Message::RenameProfile(selected, name.trim().to_string())),
+            ProfileEdit::Delete => {
+                match profile::delete(&self.root, &selected) {
+                    Ok(()) => {
+                        self.refresh(&self.current.clone());
+                        self.status = Some(format!("Profile {} is removed", selected));
+                    }
+                    Err(e) => self.status = Some(e),
+                }
+                None
+            }
+        }
+    }
+
+    fn edit_text(&mut self) -> Option<&mut String> {
+        match &mut self.edit {
+            Some(ProfileEdit::Create(text)) | Some(ProfileEdit::Rename(text)) => Some(text),
+            _ => None,
+        }
+    }
+}
+impl Component for ProfileComp {
+    fn handle_message(&mut self, msg: Message) -> Message {
+        let answer = match msg {
+            //typing name or confirming removal
+            Message::Action(action) if self.edit.is_some() => match action {
+                Action::Back => {
+                    self.edit = None;
+                    None
+                }
+                Action::Confirm => self.confirm_edit(),
+                Action::DeleteChar => {
+                    if let Some(text) = self.edit_text() {
+                        text.pop();
+                    }
+                    None
+                }
+                _ => None,
+            },
+            Message::KeyInput(key) if self.edit.is_some() => {
+                if let (Some(c), Some(text)) = (typed_char(&key), self.edit_text()) {
+                    text.push(c);
+                }
+                None
+            }
+            Message::Paste(ref text) if self.edit.is_some() => {
+                if let Some(name) = self.edit_text() {
+                    name.push_str(&pasted_line(text));
+                }
+                None
+            }
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::OpenMenu),
+                Action::Up => {
+                    self.selected = self.selected.checked_sub(1).unwrap_or(self.names.len().saturating_sub(1));
+                    None
+                }
+                Action::Down => {
+                    self.selected = if self.selected + 1 < self.names.len() { self.selected + 1 } else { 0 };
+                    None
+                }
+                Action::Confirm => self.selected_name().map(Message::SwitchProfile),
+                Action::Add => {
+                    self.status = None;
+                    self.edit = Some(ProfileEdit::Create(String::new()));
+                    None
+                }
+                Action::Rename => {
+                    self.status = None;
+                    self.edit = self.selected_name().map(ProfileEdit::Rename);
+                    None
+                }
+                Action::Remove if self.selected_name() == Some(self.current.clone()) => {
+                    self.status = Some("Profile in use can't be removed, switch to another one first".to_string());
+                    None
+                }
+                Action::Remove => {
+                    self.status = None;
+                    self.edit = self.selected_name().map(|_| ProfileEdit::Delete);
+                    None
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match answer {
+            Some(a) => a,
+            None => msg,
+        }
+    }
+
+    fn key_context(&self) -> KeyContext {
+        match self.edit {
+            Some(ProfileEdit::Create(_)) | Some(ProfileEdit::Rename(_)) => KeyContext::Typing,
+            _ => KeyContext::Navigation,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        (50, 10)
+    }
+
+    fn view(&mut self, f: &mut Frame) {
+        let hints = self.keymap.hints(
+            KeyContext::Navigation,
+            &[
+                (&[Action::Confirm], "use"),
+                (&[Action::Add], "add"),
+                (&[Action::Rename], "rename"),
+                (&[Action::Remove], "remove"),
+            ],
+        );
+        let block = Block::bordered()
+            .title(format!("Profiles | {}", hints))
+            .padding(Padding::vertical(1))
+            .style(Style::new().white().on_black());
+        let area = block.inner(f.size());
+        f.render_widget(block, f.size());
+        let layout = Layout::vertical([Constraint::Min(1), Constraint::Length(2)]).split(area);
+
+        let lines: Vec<Line> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(i, name)| {
+                let text = if *name == self.current { format!("{} (in use)", name) } else { name.clone() };
+                if i == self.selected {
+                    Line::from(Span::styled(text, Style::new().black().on_white()))
+                } else {
+                    Line::from(text)
+                }
+            })
+            .collect();
+        //keeps selected profile visible in long lists
+        let scroll = (self.selected as u16).saturating_sub(layout[0].height.saturating_sub(1));
+        f.render_widget(
+            Paragraph::new(lines).alignment(Alignment::Center).bold().scroll((scroll, 0)),
+            layout[0],
+        );
+
+        let selected = self.selected_name().unwrap_or_default();
+        let confirm = |what: &str| {
+            self.keymap.hints(self.key_context(), &[(&[Action::Confirm], what), (&[Action::Back], "cancel")])
+        };
+        let bottom = match &self.edit {
+            Some(ProfileEdit::Create(name)) => format!("new profile:{}_ | {}", name, confirm("create")),
+            Some(ProfileEdit::Rename(name)) => format!("rename {} to:{}_ | {}", selected, name, confirm("rename")),
+            Some(ProfileEdit::Delete) => {
+                format!("Remove {} with all its results? {}", selected, confirm("remove"))
+            }
+            None => self.status.clone().unwrap_or_default(),
+        };
+        f.render_widget(
+            Paragraph::new(bottom).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+            layout[1],
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{HeadlessRunner, TestDir};
+    use crate::model::Model;
+
+    #[test]
+    fn profiles_are_created_switched_renamed_and_removed() {
+        let dir = TestDir::new("profiles_are_created_switched_renamed_and_removed");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 100, 20);
+        runner.key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter);
+        assert!(runner.screen().contains("test (in use)"));
+        runner.key(KeyCode::Char('a')).type_text("anna").key(KeyCode::Enter);
+        assert!(runner.screen().contains("Profile anna is created"));
+
+        //new profile is selected after creation
+        runner.key(KeyCode::Enter);
+        assert!(matches!(runner.model.active_window, WindowType::Menu(_)));
+        assert_eq!(runner.model.profile.name, "anna");
+        let anna_dir = runner.model.profile.dir();
+        assert!(runner.model.settings.paths.history.starts_with(anna_dir.to_str().unwrap()));
+
+        runner.key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter);
+        runner.key(KeyCode::Delete);
+        assert!(runner.screen().contains("Profile in use can't be removed"));
+        runner.key(KeyCode::Down).key(KeyCode::Delete);
+        assert!(runner.screen().contains("Remove test with all its results?"));
+        runner.key(KeyCode::Enter);
+        assert!(runner.screen().contains("Profile test is removed"));
+
+        runner.key(KeyCode::F(2)).key(KeyCode::Backspace).type_text("a K").key(KeyCode::Enter);
+        assert!(runner.screen().contains("Profile anna is renamed to anna K"));
+        assert_eq!(runner.model.profile.name, "anna K");
+        assert!(runner.model.settings.paths.history.starts_with(runner.model.profile.dir().to_str().unwrap()));
+        assert_eq!(profile::list(&runner.model.profile.root), vec!["anna K"]);
+        assert!(!anna_dir.exists());
+    }
+
+    #[test]
+    fn profile_keys_follow_keymap() {
+        let dir = TestDir::new("profile_keys_follow_keymap");
+        let mut settings = dir.settings();
+        let keymap = &mut settings.input.keymap;
+        keymap.navigation.remove("a");
+        keymap.navigation.insert("Insert".to_string(), Action::Add);
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 100, 20);
+        runner.key(KeyCode::Down).key(KeyCode::Down).key(KeyCode::Enter);
+        assert!(runner.screen().contains("Profiles | Enter/Space = use | Insert = add | F2 = rename"));
+        runner.key(KeyCode::Char('a'));
+        assert_eq!(runner.model.active_window.key_context(), KeyContext::Navigation);
+        runner.key(KeyCode::Insert);
+        assert!(runner.screen().contains("new profile:_ | Enter = create | Esc = cancel"));
+    }
+}
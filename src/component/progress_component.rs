@@ -0,0 +1,289 @@
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::symbols::Marker;
+use ratatui::widgets::{Axis, Chart, Dataset, GraphType};
+
+use super::*;
+use crate::game::{GameConf, GameMode, Limit, Strictness, TextSource};
+use crate::history::RunRecord;
+use crate::keymap::{Action, KeyContext, KeyMap};
+use crate::progress::{self, Period, PeriodSummary};
+
+///number of periods in rolling average
+const ROLLING_WINDOW: usize = 7;
+
+/// component responsible for window with progress over time
+#[derive(Debug)]
+pub struct ProgressComp {
+    ///counted runs from history
+    pub records: Vec<RunRecord>,
+    pub period: Period,
+    ///every configuration found in records
+    pub confs: Vec<GameConf>,
+    ///index of configuration shown, `None` shows every run
+    pub filter: Option<usize>,
+    ///bindings shown in window title
+    pub keymap: KeyMap,
+}
+impl ProgressComp {
+    pub fn new(records: Vec<RunRecord>, keymap: KeyMap) -> ProgressComp {
+        let records: Vec<RunRecord> = records.into_iter().filter(|r| r.counts_as_record()).collect();
+        let mut confs: Vec<GameConf> = Vec::new();
+        for record in &records {
+            if !confs.contains(&record.game_conf) {
+                confs.push(record.game_conf.clone());
+            }
+        }
+        ProgressComp {
+            records,
+            period: Period::Day,
+            confs,
+            filter: None,
+            keymap,
+        }
+    }
+    ///select next or previous configuration filter, after last configuration comes "all"
+    fn change_filter(&mut self, forward: bool) {
+        let len = self.confs.len();
+        if len == 0 {
+            return;
+        }
+        self.filter = match (self.filter, forward) {
+            (None, true) => Some(0),
+            (None, false) => Some(len - 1),
+            (Some(i), true) if i + 1 < len => Some(i + 1),
+            (Some(i), false) if i > 0 => Some(i - 1),
+            _ => None,
+        };
+    }
+    fn filtered_records(&self) -> Vec<RunRecord> {
+        match self.filter {
+            Some(i) => self.records.iter().filter(|r| r.game_conf == self.confs[i]).cloned().collect(),
+            None => self.records.clone(),
+        }
+    }
+}
+/// short description of configuration
+fn conf_label(conf: &GameConf) -> String {
+    let mode = match conf.mode {
+        GameMode::Normal => "normal",
+        GameMode::Rewrite => "rewrite",
+    };
+    let limit = match &conf.limit {
+        Limit::Time(t) => format!("{}s", t.as_secs()),
+        Limit::WordCount(wc) => format!("{} words", wc),
+        Limit::None => "whole text".to_string(),
+    };
+    let source = match &conf.text_source {
+        TextSource::Generated => format!("generated {}", conf.language),
+        TextSource::File(path) => path.clone(),
+    };
+    let strictness = match conf.strictness {
+        Strictness::Normal => "",
+        Strictness::Strict => ", strict",
+    };
+    format!("{}, {}, {}{}", mode, limit, source, strictness)
+}
+/// draws values of every period with rolling average
+fn render_chart(f: &mut Frame, area: Rect, title: &str, period: Period, summaries: &[PeriodSummary], values: Vec<f64>, bounds: [f64; 2]) {
+    let points: Vec<(f64, f64)> = values.iter().enumerate().map(|(i, v)| (i as f64, *v)).collect();
+    let average: Vec<(f64, f64)> = progress::rolling_average(&values, ROLLING_WINDOW)
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| (i as f64, v))
+        .collect();
+    let last = summaries.len().saturating_sub(1);
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .name(title.to_string())
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().white())
+            .data(&points),
+        Dataset::default()
+            .name(format!("{}-{} average", ROLLING_WINDOW, period.name()))
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().green())
+            .data(&average),
+    ])
+    .block(Block::bordered().title(title.to_string()))
+    .x_axis(
+        Axis::default()
+            .bounds([0.0, last.max(1) as f64])
+            .labels(vec![
+                Span::from(progress::date_label(summaries[0].day)),
+                Span::from(progress::date_label(summaries[last].day)),
+            ]),
+    )
+    .y_axis(
+        Axis::default()
+            .bounds(bounds)
+            .labels(vec![Span::from(format!("{}", bounds[0].round())), Span::from(format!("{}", bounds[1].round()))]),
+    )
+    .hidden_legend_constraints((Constraint::Ratio(1, 1), Constraint::Ratio(1, 1))); //legend is always shown
+    f.render_widget(chart, area);
+}
+impl Component for ProgressComp {
+    fn handle_message(&mut self, msg: Message) -> Message {
+        let answer = match msg {
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::OpenMenu),
+                Action::Left => {
+                    self.change_filter(false);
+                    None
+                }
+                Action::Right => {
+                    self.change_filter(true);
+                    None
+                }
+                Action::Up | Action::Down => {
+                    self.period = match self.period {
+                        Period::Day => Period::Week,
+                        Period::Week => Period::Day,
+                    };
+                    None
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match answer {
+            Some(a) => a,
+            None => msg,
+        }
+    }
+
+    fn min_size(&self) -> (u16, u16) {
+        (50, 16)
+    }
+
+    fn view(&mut self, f: &mut Frame) {
+        let block = Block::bordered()
+            .title(format!(
+                "Progress | {}",
+                self.keymap.hints(
+                    KeyContext::Navigation,
+                    &[
+                        (&[Action::Back], "go to menu"),
+                        (&[Action::Left, Action::Right], "configuration"),
+                        (&[Action::Up, Action::Down], "days/weeks"),
+                    ],
+                )
+            ))
+            .style(Style::new().white().on_black());
+        let area = block.inner(f.size());
+        f.render_widget(block, f.size());
+
+        let records = self.filtered_records();
+        let filter = match self.filter {
+            Some(i) => conf_label(&self.confs[i]),
+            None => "all configurations".to_string(),
+        };
+        let totals = progress::totals(&records);
+        let minutes = (totals.time_secs / 60.0).round() as u64;
+        let mut summary = vec![
+            if totals.imported > 0 {
+                format!("{} runs ({} imported)", totals.runs, totals.imported)
+            } else {
+                format!("{} runs", totals.runs)
+            },
+            format!("{}h {}m typed", minutes / 60, minutes % 60),
+            format!("{} words", totals.words),
+        ];
+        if let Some(best) = &totals.best_day {
+            summary.push(format!("best day {} ({} WPM)", progress::date_label(best.day), best.wpm.round()));
+        }
+        let layout = Layout::vertical([
+            Constraint::Length(2),
+            Constraint::Percentage(60),
+            Constraint::Percentage(40),
+        ])
+        .split(area);
+        f.render_widget(
+            Paragraph::new(vec![
+                Line::from(format!("{} | by {}", filter, self.period.name())).bold(),
+                Line::from(summary.join(" | ")),
+            ]),
+            layout[0],
+        );
+
+        let summaries = progress::summarize(&records, self.period);
+        if summaries.is_empty() {
+            f.render_widget(
+                Paragraph::new("No finished runs yet").alignment(Alignment::Center),
+                layout[1],
+            );
+            return;
+        }
+        let wpm: Vec<f64> = summaries.iter().map(|s| s.wpm).collect();
+        let max_wpm = wpm.iter().cloned().fold(10.0, f64::max);
+        render_chart(f, layout[1], "WPM", self.period, &summaries, wpm, [0.0, max_wpm]);
+        let accuracy: Vec<f64> = summaries.iter().map(|s| s.accuracy).collect();
+        //accuracy rarely goes low, lower part of the chart would be empty
+        let min_accuracy = accuracy.iter().cloned().fold(100.0, f64::min);
+        let lowest = ((min_accuracy - 5.0) / 10.0).floor().max(0.0) * 10.0;
+        render_chart(f, layout[2], "Accuracy %", self.period, &summaries, accuracy, [lowest, 100.0]);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::history::Origin;
+    use crate::model::Model;
+    use typing_engine::CheatFlag;
+
+    /// history of two configurations over two weeks
+    fn progress_records() -> Vec<RunRecord> {
+        let day = 24 * 60 * 60;
+        (0..12)
+            .map(|i| RunRecord {
+                finished_at: 1717200000 + i * day + (i % 2) * day / 3,
+                game_conf: GameConf {
+                    limit: Limit::WordCount(if i % 3 == 0 { 10 } else { 25 }),
+                    ..GameConf::new()
+                },
+                duration_secs: 60.0,
+                wpm: 40.0 + i as f64 * 2.0 + (i % 3) as f64 * 5.0,
+                raw_wpm: 60.0,
+                accuracy: 90.0 + (i % 4) as f64,
+                correct_words: 40,
+                total_words: 42,
+                correct_strokes: 0,
+                wrong_strokes: 0,
+                cheat_flags: if i == 5 { vec![CheatFlag::Paste] } else { Vec::new() },
+                typed_text: String::new(),
+                origin: if i == 0 { Origin::Monkeytype } else { Origin::Local },
+            })
+            .collect()
+    }
+
+    #[test]
+    fn progress_window() {
+        let dir = TestDir::new("progress_window");
+        let settings = dir.settings();
+        let mut history = crate::history::History::new(&settings.paths.history);
+        for record in progress_records() {
+            history.add(record);
+        }
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 100, 30);
+        runner.key(KeyCode::Down).key(KeyCode::Enter);
+        let screen = runner.screen();
+        assert!(screen.contains("all configurations | by day"));
+        //invalid run is not counted
+        assert!(screen.contains("11 runs (1 imported) | 0h 11m typed | 462 words"));
+        runner.key(KeyCode::Right);
+        assert!(runner.screen().contains("normal, 10 words, generated en | by day"));
+        assert!(runner.screen().contains("4 runs"));
+        runner.key(KeyCode::Left).key(KeyCode::Down);
+        assert!(runner.screen().contains("all configurations | by week"));
+        runner.key(KeyCode::Esc);
+        assert!(matches!(runner.model.active_window, WindowType::Menu(_)));
+    }
+
+    #[test]
+    fn window_snapshots() {
+        assert_window_snapshots("progress", WindowType::Progress(ProgressComp::new(progress_records(), KeyMap::default())));
+    }
+}
@@ -0,0 +1,641 @@
+use std::time::Duration;
+
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use ratatui::layout::Rect;
+use ratatui::widgets::Padding;
+use unicode_width::UnicodeWidthChar;
+
+use super::*;
+use crate::config_manager::{self, HudPlacement, Settings};
+use crate::game::{GameMode, Limit, Strictness};
+use crate::keymap::{Action, KeyContext, KeyMap, KeymapPreset};
+
+/// component responsible for settings window
+#[derive(Debug)]
+pub struct SettingsComp {
+    pub settings: Settings,
+    ///file settings are saved to
+    pub path: String,
+    pub option: SettingsOption,
+    ///text typed into path field, `None` if path is not being edited
+    pub path_input: Option<String>,
+    ///result of last change
+    pub status: Option<String>,
+    ///rows of option list hidden above the window
+    pub scroll: u16,
+    ///ids and names of keyboard layouts to choose from
+    pub layouts: Vec<(String, String)>,
+    ///custom key bindings wait for confirmation to be replaced with a preset
+    pub replacing_keymap: bool,
+}
+
+///group of settings shown under one title
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SettingsSection {
+    Appearance,
+    Gameplay,
+    Input,
+    Sound,
+    Paths,
+}
+impl SettingsSection {
+    fn title(&self) -> &str {
+        match self {
+            SettingsSection::Appearance => "Appearance",
+            SettingsSection::Gameplay => "Gameplay defaults",
+            SettingsSection::Input => "Input",
+            SettingsSection::Sound => "Sound",
+            SettingsSection::Paths => "Data paths",
+        }
+    }
+}
+
+///setting selected for change
+#[derive(Debug, PartialEq, FromPrimitive, Clone, Copy)]
+pub enum SettingsOption {
+    ShowWpm,
+    ShowRawWpm,
+    ShowAccuracy,
+    ShowProgress,
+    HudPlacement,
+    ShowKeyboard,
+    DefaultMode,
+    DefaultLimit,
+    DefaultLimitValue,
+    DefaultStrictness,
+    KeyBindings,
+    Layout,
+    EmulatedLayout,
+    BellOnError,
+    BellOnFinish,
+    LanguagesPath,
+    LayoutsPath,
+    HistoryPath,
+    GameConfsPath,
+    ExportsPath,
+}
+impl SettingsOption {
+    ///select next option
+    pub fn next(&mut self) {
+        let i = *self as i32 + 1;
+        *self = match FromPrimitive::from_i32(i) {
+            Some(opt) => opt,
+            None => SettingsOption::ShowWpm,
+        }
+    }
+    ///select previous option
+    pub fn prev(&mut self) {
+        let i = *self as i32 - 1;
+        *self = match FromPrimitive::from_i32(i) {
+            Some(opt) => opt,
+            None => SettingsOption::ExportsPath,
+        }
+    }
+    fn section(&self) -> SettingsSection {
+        match self {
+            SettingsOption::ShowWpm
+            | SettingsOption::ShowRawWpm
+            | SettingsOption::ShowAccuracy
+            | SettingsOption::ShowProgress
+            | SettingsOption::HudPlacement
+            | SettingsOption::ShowKeyboard => SettingsSection::Appearance,
+            SettingsOption::DefaultMode
+            | SettingsOption::DefaultLimit
+            | SettingsOption::DefaultLimitValue
+            | SettingsOption::DefaultStrictness => SettingsSection::Gameplay,
+            SettingsOption::KeyBindings | SettingsOption::Layout | SettingsOption::EmulatedLayout => {
+                SettingsSection::Input
+            }
+            SettingsOption::BellOnError | SettingsOption::BellOnFinish => SettingsSection::Sound,
+            SettingsOption::LanguagesPath
+            | SettingsOption::LayoutsPath
+            | SettingsOption::HistoryPath
+            | SettingsOption::GameConfsPath
+            | SettingsOption::ExportsPath => SettingsSection::Paths,
+        }
+    }
+    fn name(&self) -> &str {
+        match self {
+            SettingsOption::ShowWpm => "HUD: words per minute",
+            SettingsOption::ShowRawWpm => "HUD: raw words per minute",
+            SettingsOption::ShowAccuracy => "HUD: accuracy",
+            SettingsOption::ShowProgress => "HUD: progress bar",
+            SettingsOption::HudPlacement => "HUD placement",
+            SettingsOption::ShowKeyboard => "On-screen keyboard",
+            SettingsOption::DefaultMode => "Mode",
+            SettingsOption::DefaultLimit => "Limit",
+            SettingsOption::DefaultLimitValue => "Limit value",
+            SettingsOption::DefaultStrictness => "Strictness",
+            SettingsOption::KeyBindings => "Key bindings",
+            SettingsOption::Layout => "Keyboard layout",
+            SettingsOption::EmulatedLayout => "Emulated layout",
+            SettingsOption::BellOnError => "Bell on mistake",
+            SettingsOption::BellOnFinish => "Bell on game end",
+            SettingsOption::LanguagesPath => "Language packs folder",
+            SettingsOption::LayoutsPath => "Keyboard layouts folder",
+            SettingsOption::HistoryPath => "History file",
+            SettingsOption::GameConfsPath => "Game configurations file",
+            SettingsOption::ExportsPath => "Exported runs folder",
+        }
+    }
+    fn value(&self, settings: &Settings, layouts: &[(String, String)]) -> String {
+        let on_off = |b: bool| if b { "on" } else { "off" }.to_string();
+        match self {
+            SettingsOption::ShowWpm => on_off(settings.hud.show_wpm),
+            SettingsOption::ShowRawWpm => on_off(settings.hud.show_raw_wpm),
+            SettingsOption::ShowAccuracy => on_off(settings.hud.show_accuracy),
+            SettingsOption::ShowProgress => on_off(settings.hud.show_progress),
+            SettingsOption::HudPlacement => format!("{:?}", settings.hud.placement),
+            SettingsOption::ShowKeyboard => on_off(settings.hud.show_keyboard),
+            SettingsOption::DefaultMode => format!("{:?}", settings.gameplay.game_conf.mode),
+            SettingsOption::DefaultLimit => match settings.gameplay.game_conf.limit {
+                Limit::Time(_) => "time",
+                Limit::WordCount(_) => "word count",
+                Limit::None => "whole text",
+            }
+            .to_string(),
+            SettingsOption::DefaultLimitValue => match settings.gameplay.game_conf.limit {
+                Limit::Time(t) => format!("{}s", t.as_secs()),
+                Limit::WordCount(wc) => format!("{} words", wc),
+                Limit::None => "-".to_string(),
+            },
+            SettingsOption::DefaultStrictness => {
+                format!("{:?}", settings.gameplay.game_conf.strictness)
+            }
+            SettingsOption::KeyBindings => match settings.input.keymap.get_preset() {
+                Some(preset) => format!("{:?}", preset),
+                None => format!("custom (edit [input.keymap] in {})", config_manager::SETTINGS_PATH),
+            },
+            SettingsOption::Layout => layout_name(layouts, &settings.input.layout),
+            SettingsOption::EmulatedLayout => match &settings.input.emulated_layout {
+                Some(id) => layout_name(layouts, id),
+                None => "off".to_string(),
+            },
+            SettingsOption::BellOnError => on_off(settings.sound.bell_on_error),
+            SettingsOption::BellOnFinish => on_off(settings.sound.bell_on_finish),
+            SettingsOption::LanguagesPath => settings.paths.languages.clone(),
+            SettingsOption::LayoutsPath => settings.paths.layouts.clone(),
+            SettingsOption::HistoryPath => settings.paths.history.clone(),
+            SettingsOption::GameConfsPath => settings.paths.game_confs.clone(),
+            SettingsOption::ExportsPath => settings.paths.exports.clone(),
+        }
+    }
+    ///changes value of the option, `forward` is false for left arrow
+    fn change(&self, settings: &mut Settings, layouts: &[(String, String)], forward: bool) {
+        let step = |value: u64, step: u64| {
+            if forward {
+                value + step
+            } else {
+                value.saturating_sub(step)
+            }
+        };
+        match self {
+            SettingsOption::ShowWpm => settings.hud.show_wpm = !settings.hud.show_wpm,
+            SettingsOption::ShowRawWpm => settings.hud.show_raw_wpm = !settings.hud.show_raw_wpm,
+            SettingsOption::ShowAccuracy => {
+                settings.hud.show_accuracy = !settings.hud.show_accuracy
+            }
+            SettingsOption::ShowProgress => {
+                settings.hud.show_progress = !settings.hud.show_progress
+            }
+            SettingsOption::HudPlacement => {
+                settings.hud.placement = match (settings.hud.placement, forward) {
+                    (HudPlacement::Top, true) | (HudPlacement::Hidden, false) => {
+                        HudPlacement::Bottom
+                    }
+                    (HudPlacement::Bottom, true) | (HudPlacement::Top, false) => {
+                        HudPlacement::Hidden
+                    }
+                    (HudPlacement::Hidden, true) | (HudPlacement::Bottom, false) => {
+                        HudPlacement::Top
+                    }
+                }
+            }
+            SettingsOption::ShowKeyboard => {
+                settings.hud.show_keyboard = !settings.hud.show_keyboard
+            }
+            SettingsOption::DefaultMode => {
+                let conf = &mut settings.gameplay.game_conf;
+                conf.mode = match conf.mode {
+                    GameMode::Normal => GameMode::Rewrite,
+                    GameMode::Rewrite => GameMode::Normal,
+                };
+            }
+            SettingsOption::DefaultLimit => {
+                let conf = &mut settings.gameplay.game_conf;
+                conf.limit = match (&conf.limit, forward) {
+                    (Limit::Time(_), true) | (Limit::None, false) => Limit::WordCount(50),
+                    (Limit::WordCount(_), true) | (Limit::Time(_), false) => Limit::None,
+                    (Limit::None, true) | (Limit::WordCount(_), false) => {
+                        Limit::Time(Duration::from_secs(30))
+                    }
+                };
+            }
+            SettingsOption::DefaultLimitValue => match &mut settings.gameplay.game_conf.limit {
+                Limit::Time(t) => *t = Duration::from_secs(step(t.as_secs(), 5)),
+                Limit::WordCount(wc) => *wc = step(*wc as u64, 5) as u32,
+                Limit::None => (),
+            },
+            SettingsOption::DefaultStrictness => {
+                let conf = &mut settings.gameplay.game_conf;
+                conf.strictness = match conf.strictness {
+                    Strictness::Normal => Strictness::Strict,
+                    Strictness::Strict => Strictness::Normal,
+                };
+            }
+            //custom key bindings are replaced only after confirmation
+            SettingsOption::KeyBindings => match settings.input.keymap.get_preset() {
+                Some(KeymapPreset::Default) => settings.input.keymap = KeyMap::preset(KeymapPreset::Vim),
+                Some(KeymapPreset::Vim) => settings.input.keymap = KeyMap::preset(KeymapPreset::Default),
+                None => (),
+            },
+            SettingsOption::Layout if !layouts.is_empty() => {
+                let i = layouts.iter().position(|(id, _)| *id == settings.input.layout).unwrap_or(0);
+                let i = if forward {
+                    (i + 1) % layouts.len()
+                } else {
+                    (i + layouts.len() - 1) % layouts.len()
+                };
+                settings.input.layout = layouts[i].0.clone();
+            }
+            SettingsOption::Layout => (),
+            SettingsOption::EmulatedLayout => {
+                //off is before the first layout
+                let choices: Vec<Option<String>> =
+                    std::iter::once(None).chain(layouts.iter().map(|(id, _)| Some(id.clone()))).collect();
+                let i = choices.iter().position(|c| *c == settings.input.emulated_layout).unwrap_or(0);
+                let i = if forward {
+                    (i + 1) % choices.len()
+                } else {
+                    (i + choices.len() - 1) % choices.len()
+                };
+                settings.input.emulated_layout = choices[i].clone();
+            }
+            SettingsOption::BellOnError => {
+                settings.sound.bell_on_error = !settings.sound.bell_on_error
+            }
+            SettingsOption::BellOnFinish => {
+                settings.sound.bell_on_finish = !settings.sound.bell_on_finish
+            }
+            SettingsOption::LanguagesPath
+            | SettingsOption::LayoutsPath
+            | SettingsOption::HistoryPath
+            | SettingsOption::GameConfsPath
+            | SettingsOption::ExportsPath => (), //edited as text
+        }
+    }
+    ///returns path this option sets, `None` if option is not a path
+    fn path_mut<'a>(&self, settings: &'a mut Settings) -> Option<&'a mut String> {
+        match self {
+            SettingsOption::LanguagesPath => Some(&mut settings.paths.languages),
+            SettingsOption::LayoutsPath => Some(&mut settings.paths.layouts),
+            SettingsOption::HistoryPath => Some(&mut settings.paths.history),
+            SettingsOption::GameConfsPath => Some(&mut settings.paths.game_confs),
+            SettingsOption::ExportsPath => Some(&mut settings.paths.exports),
+            _ => None,
+        }
+    }
+}
+
+/// name of the layout with the id, id itself if there is no such layout
+fn layout_name(layouts: &[(String, String)], id: &str) -> String {
+    layouts
+        .iter()
+        .find(|(layout_id, _)| layout_id == id)
+        .map(|(_, name)| name.clone())
+        .unwrap_or(id.to_string())
+}
+
+impl SettingsComp {
+    pub fn new(settings: Settings, path: String, layouts: Vec<(String, String)>) -> SettingsComp {
+        SettingsComp {
+            settings,
+            path,
+            option: SettingsOption::ShowWpm,
+            path_input: None,
+            status: None,
+            scroll: 0,
+            layouts,
+            replacing_keymap: false,
+        }
+    }
+    /// validates changed settings and saves them, returns message with new settings if they are accepted
+    fn apply(&mut self, changed: Settings) -> Option<Message> {
+        if let Err(e) = changed.validate() {
+            self.status = Some(format!("Not saved: {}", e));
+            return None;
+        }
+        if let Err(e) = config_manager::save_settings(&self.path, &changed) {
+            self.status = Some(format!("Failed to save settings: {}", e));
+            return None;
+        }
+        self.status = Some("Saved".to_string());
+        self.settings = changed;
+        Some(Message::SettingsChanged(self.settings.clone()))
+    }
+}
+
+impl Component for SettingsComp {
+    fn handle_message(&mut self, msg: Message) -> Message {
+        let answer = match msg {
+            //typing path
+            Message::Action(action) if self.path_input.is_some() => match action {
+                Action::Back => {
+                    self.path_input = None;
+                    None
+                }
+                Action::Confirm => {
+                    let mut changed = self.settings.clone();
+                    if let Some(path) = self.option.path_mut(&mut changed) {
+                        *path = self.path_input.take().unwrap();
+                    }
+                    self.apply(changed)
+                }
+                Action::DeleteChar => {
+                    self.path_input.as_mut().unwrap().pop();
+                    None
+                }
+                _ => None,
+            },
+            Message::KeyInput(key) if self.path_input.is_some() => {
+                if let Some(c) = typed_char(&key) {
+                    self.path_input.as_mut().unwrap().push(c);
+                }
+                None
+            }
+            Message::Paste(ref text) if self.path_input.is_some() => {
+                self.path_input.as_mut().unwrap().push_str(&pasted_line(text));
+                None
+            }
+            Message::Action(action) if self.replacing_keymap => {
+                self.replacing_keymap = false;
+                if action == Action::Confirm {
+                    let mut changed = self.settings.clone();
+                    changed.input.keymap = KeyMap::preset(KeymapPreset::Default);
+                    self.apply(changed)
+                } else {
+                    self.status = Some("Custom key bindings are kept".to_string());
+                    None
+                }
+            }
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::OpenMenu),
+                Action::Left | Action::Right | Action::Confirm
+                    if self.option == SettingsOption::KeyBindings && self.settings.input.keymap.get_preset().is_none() =>
+                {
+                    self.replacing_keymap = true;
+                    self.status = Some(format!(
+                        "Replace custom key bindings with Default preset? {}",
+                        self.settings.input.keymap.hints(
+                            KeyContext::Navigation,
+                            &[(&[Action::Confirm], "replace"), (&[Action::Back], "keep")]
+                        )
+                    ));
+                    None
+                }
+                Action::Down => {
+                    self.option.next();
+                    None
+                }
+                Action::Up => {
+                    self.option.prev();
+                    None
+                }
+                Action::Left | Action::Right => {
+                    let mut changed = self.settings.clone();
+                    self.option.change(&mut changed, &self.layouts, action == Action::Right);
+                    self.apply(changed)
+                }
+                Action::Confirm => {
+                    let mut settings = self.settings.clone();
+                    if let Some(path) = self.option.path_mut(&mut settings) {
+                        self.path_input = Some(path.clone());
+                        None
+                    } else {
+                        let mut changed = self.settings.clone();
+                        self.option.change(&mut changed, &self.layouts, true);
+                        self.apply(changed)
+                    }
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        match answer {
+            Some(a) => a,
+            None => msg,
+        }
+    }
+
+    fn key_context(&self) -> KeyContext {
+        if self.path_input.is_some() {
+            KeyContext::Typing
+        } else {
+            KeyContext::Navigation
+        }
+    }
+    fn min_size(&self) -> (u16, u16) {
+        (40, 12)
+    }
+
+    /// render settings window
+    fn view(&mut self, f: &mut Frame) {
+        let block = Block::new()
+            .title(format!(
+                "Settings | {}",
+                self.settings.input.keymap.hints(
+                    KeyContext::Navigation,
+                    &[
+                        (&[Action::Back], "go to menu"),
+                        (&[Action::Up, Action::Down], "navigation"),
+                        (&[Action::Left, Action::Right], "change value"),
+                        (&[Action::Confirm], "edit"),
+                    ],
+                )
+            ))
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(2));
+        let mut list_area = block.inner(f.size());
+        f.render_widget(block.style(Style::new().white().on_black()), f.size());
+        //status stays under the list so it is seen wherever the list is scrolled
+        if let Some(status) = &self.status {
+            list_area.height = list_area.height.saturating_sub(2);
+            f.render_widget(
+                Paragraph::new(status.as_str()),
+                Rect {
+                    y: list_area.bottom() + 1,
+                    height: 1,
+                    ..list_area
+                },
+            );
+        }
+
+        let width = list_area.width as usize;
+        let mut lines: Vec<Line> = Vec::new();
+        //rows of selected option and title of its section
+        let mut selected = (0, 0);
+        let mut section_row = 0;
+        let mut section = None;
+        let mut option = SettingsOption::ShowWpm;
+        loop {
+            if section != Some(option.section()) {
+                section = Some(option.section());
+                lines.push(Line::from(""));
+                section_row = lines.len();
+                lines.push(Line::from(Span::styled(
+                    option.section().title().to_string(),
+                    Style::new().bold().underlined(),
+                )));
+            }
+            let value = match &self.path_input {
+                Some(input) if option == self.option => format!("{}_", input),
+                _ => option.value(&self.settings, &self.layouts),
+            };
+            let style = if option == self.option {
+                Style::new().black().on_white()
+            } else {
+                Style::new()
+            };
+            let rows = wrap_text(&format!("{}: {}", option.name(), value), width);
+            if option == self.option {
+                selected = (section_row, lines.len() + rows.len());
+            }
+            lines.extend(rows.into_iter().map(|row| Line::from(Span::styled(row, style))));
+            option.next();
+            if option == SettingsOption::ShowWpm {
+                break;
+            }
+        }
+
+        //list is scrolled only as much as needed to show selected option, with its section title if it fits
+        let (title, end) = selected;
+        let height = list_area.height as usize;
+        //bigger window shows more of the list instead of empty rows
+        self.scroll = self.scroll.min(lines.len().saturating_sub(height) as u16);
+        if end > self.scroll as usize + height {
+            self.scroll = end.saturating_sub(height) as u16;
+        }
+        let top = if end - title <= height { title } else { end.saturating_sub(height) };
+        if top < self.scroll as usize {
+            self.scroll = top as u16;
+        }
+        f.render_widget(Paragraph::new(lines).scroll((self.scroll, 0)), list_area);
+    }
+}
+
+/// splits text into rows of given width, long words are split too so every row is known
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut rows = vec![String::new()];
+    let mut row_width = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if row_width + w > width.max(1) && row_width > 0 {
+            rows.push(String::new());
+            row_width = 0;
+        }
+        rows.last_mut().unwrap().push(c);
+        row_width += w;
+    }
+    rows
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::model::Model;
+    use crate::layout;
+
+    #[test]
+    fn selected_option_is_always_visible() {
+        let dir = TestDir::new("selected_option_is_always_visible");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 40, 12);
+        runner.send(Message::OpenSettings);
+        for (width, height) in [(40, 12), (80, 24)] {
+            runner.resize(width, height);
+            for key in [KeyCode::Down, KeyCode::Up] {
+                for _ in 0..30 {
+                    runner.key(key);
+                    let option = match &runner.model.active_window {
+                        WindowType::Settings(comp) => comp.option,
+                        _ => panic!("settings window is expected"),
+                    };
+                    let screen = runner.screen();
+                    assert!(screen.contains(option.name()), "{:?} is not visible in {}x{}", option, width, height);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn layout_is_chosen_in_settings() {
+        let dir = TestDir::new("layout_is_chosen_in_settings");
+        dir.file("layouts/mine.toml", "name = \"Mine\"\nrows = [\"1234567890\", \"qwfpgjluy\", \"arstdhneio\", \" zxcvbkm\"]");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 80, 30);
+        runner.send(Message::OpenSettings);
+        for _ in 0..11 {
+            runner.key(KeyCode::Down);
+        }
+        assert!(runner.screen().contains("Keyboard layout: QWERTY"));
+        runner.key(KeyCode::Right);
+        assert!(runner.screen().contains("Keyboard layout: Dvorak"));
+        //user layout comes after built-in ones
+        runner.key(KeyCode::Left).key(KeyCode::Left);
+        assert!(runner.screen().contains("Keyboard layout: Mine"));
+        assert_eq!(runner.model.settings.input.layout, "mine");
+        assert_eq!(runner.model.layouts.len(), 7);
+    }
+
+    #[test]
+    fn game_configurations_file_is_chosen_in_settings() {
+        let dir = TestDir::new("game_configurations_file_is_chosen_in_settings");
+        let path = dir.file("other/presets.toml", "[[presets]]\nname = \"sprint\"\n[presets.game_conf]\n");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 200, 30);
+        runner.send(Message::OpenSettings);
+        runner.key(KeyCode::Up).key(KeyCode::Up);
+        let old = runner.model.settings.paths.game_confs.clone();
+        assert!(runner.screen().contains(&format!("Game configurations file: {}", old)));
+        runner.key(KeyCode::Enter);
+        for _ in 0..old.chars().count() {
+            runner.key(KeyCode::Backspace);
+        }
+        runner.type_text(&path).key(KeyCode::Enter);
+        assert!(runner.screen().contains("Saved"));
+        assert_eq!(runner.model.settings.paths.game_confs, path);
+        assert_eq!(runner.model.game_confs.presets[0].name, "sprint");
+    }
+
+    #[test]
+    fn custom_key_bindings_are_replaced_after_confirmation() {
+        let dir = TestDir::new("custom_key_bindings_are_replaced_after_confirmation");
+        let mut settings = dir.settings();
+        settings.input.keymap.navigation.insert("x".to_string(), Action::Back);
+        let custom = settings.input.keymap.clone();
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 30);
+        runner.send(Message::OpenSettings);
+        for _ in 0..10 {
+            runner.key(KeyCode::Down);
+        }
+        runner.key(KeyCode::Right);
+        assert!(runner.screen().contains("Replace custom key bindings with Default preset? Enter/Space = replace"));
+        runner.key(KeyCode::Esc);
+        assert!(runner.screen().contains("Custom key bindings are kept"));
+        assert_eq!(runner.model.settings.input.keymap, custom);
+        assert!(matches!(runner.model.active_window, WindowType::Settings(_)));
+        runner.key(KeyCode::Left).key(KeyCode::Enter);
+        assert!(runner.screen().contains("Key bindings: Default"));
+        runner.key(KeyCode::Right);
+        assert!(runner.screen().contains("Key bindings: Vim"));
+        assert_eq!(runner.model.settings.input.keymap.get_preset(), Some(KeymapPreset::Vim));
+    }
+
+    #[test]
+    fn window_snapshots() {
+        //the last option is selected, list has to be scrolled to show it
+        let layouts = layout::bundled().into_iter().map(|l| (l.id, l.name)).collect();
+        let mut comp = SettingsComp::new(Settings::default(), "settings.toml".to_string(), layouts);
+        comp.option.prev();
+        assert_window_snapshots("settings", WindowType::Settings(comp));
+    }
+}
@@ -1,12 +1,17 @@
 
+use std::time::Duration;
+
 use num_traits::Zero;
 use ratatui::{
-    layout::{Constraint, Layout},
-    style::Color,
-    widgets::canvas::{Canvas, Rectangle},
+    layout::{Constraint, Layout, Rect},
+    symbols::Marker,
+    widgets::{Axis, Chart, Dataset, GraphType},
 };
 
+use crate::finger_stats::FingerStats;
 use crate::game::GameStat;
+use crate::history::{PersonalBest, RunRecord};
+use crate::keymap::{Action, KeyContext, KeyMap};
 
 use super::*;
 
@@ -15,14 +20,32 @@ pub struct StatComp {
     pub game: Game,
     ///statistics
     pub statistics: Option<GameStat>,
+    ///comparison with personal best, `None` if run doesn't count
+    pub personal_best: Option<PersonalBest>,
+    ///result of last export
+    pub status: Option<String>,
+    ///finger and hand statistics counted for layout from settings
+    pub fingers: FingerStats,
+    ///key bindings shown in help
+    pub keymap: KeyMap,
 }
 #[allow(unused_variables)]
 #[allow(dead_code)]
 impl Component for StatComp {
     fn handle_message(&mut self, msg: Message) -> Message {
         let answer = match msg {
-            Message::KeyInput(key) => match key.code {
-                KeyCode::Esc => Some(Message::GoToWindow(WindowType::Menu(MenuComp::new()))),
+            Message::Action(action) => match action {
+                Action::Back => Some(Message::OpenMenu),
+                Action::Restart => Some(Message::RestartGame(self.game.clone())),
+                Action::NextText => Some(Message::StartGame(self.game.game_conf.clone())),
+                Action::GameConfig => Some(Message::OpenGameConfig),
+                Action::Export => {
+                    let stats = match &self.statistics {
+                        Some(stats) => stats.clone(),
+                        None => self.game.clone().get_statistics(),
+                    };
+                    Some(Message::ExportRun(RunRecord::new(&self.game, &stats)))
+                }
                 _ => None,
             },
             _ => None,
@@ -33,6 +56,10 @@ impl Component for StatComp {
         }
     }
 
+    fn min_size(&self) -> (u16, u16) {
+        (80, 12) //numbers don't fit on narrower screen
+    }
+
     fn view(&mut self, f: &mut Frame) {
         // let stats = &self.game.get_statistics();
         let stats = if let Some(stats) = &self.statistics {
@@ -41,12 +68,56 @@ impl Component for StatComp {
             self.statistics = Some(self.game.get_statistics());
             self.game.get_statistics()
         };
-        f.render_widget(Block::bordered().title("Statistics | Esc = got to menu"), f.size());
+        let hints = self.keymap.hints(
+            KeyContext::Navigation,
+            &[
+                (&[Action::Back], "go to menu"),
+                (&[Action::Restart], "same text again"),
+                (&[Action::NextText], "new text"),
+                (&[Action::GameConfig], "game configuration"),
+                (&[Action::Export], "export"),
+            ],
+        );
+        let block = Block::bordered().title(format!("Statistics | {}", hints));
+        let inner = block.inner(f.size());
+        f.render_widget(block, f.size());
+        let notice = if !stats.cheat_flags.is_empty() {
+            let reasons: Vec<&str> = stats.cheat_flags.iter().map(|flag| flag.description()).collect();
+            Some(Span::from(format!("Run is not counted: {}", reasons.join(", "))).red())
+        } else {
+            self.personal_best.as_ref().map(|best| match best.delta() {
+                Some(delta) if best.is_new() => {
+                    Span::from(format!("New personal best! +{:.1} WPM", delta)).green().bold()
+                }
+                None => Span::from("First personal best for this configuration!").green().bold(),
+                Some(delta) => Span::from(format!(
+                    "Personal best: {} WPM ({:.1} WPM)",
+                    best.previous_wpm.unwrap_or_default().round(),
+                    delta
+                )),
+            })
+        };
+        if let Some(notice) = notice {
+            f.render_widget(
+                Paragraph::new(Line::from(notice)).alignment(Alignment::Center),
+                Rect { height: 1, ..inner },
+            );
+        }
         let zones_layout = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
             .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-            .split(f.size());
+            .split(inner);
         let number_area = zones_layout[0];
+        if let Some(status) = &self.status {
+            f.render_widget(
+                Paragraph::new(status.as_str()).alignment(Alignment::Center).wrap(Wrap { trim: true }),
+                Rect {
+                    y: number_area.bottom().saturating_sub(1),
+                    height: 1,
+                    ..inner
+                },
+            );
+        }
         let number_layout = Layout::new(
             ratatui::layout::Direction::Horizontal,
             [
@@ -55,30 +126,20 @@ impl Component for StatComp {
                 Constraint::Ratio(1, 3),
             ],
         )
-        .vertical_margin((number_area.height - 1) / 2)
+        .vertical_margin(number_area.height.saturating_sub(1) / 2)
         .split(number_area);
 
         let number_paragraph = |line: String| -> Paragraph {
             Paragraph::new(Line::from(line).alignment(Alignment::Center))
         };
 
-        let time_spent_in_minutes = stats
-            .time_finished
-            .duration_since(stats.time_started)
-            .unwrap()
-            .as_secs_f32()
-            / 60 as f32;
-
         f.render_widget(
-            number_paragraph(format!(
-                "Total words per minute: {}",
-                (stats.correct_words as f32 / time_spent_in_minutes).round()
-            )), //?SPEED
+            number_paragraph(format!("WPM: {}", stats.wpm().round())),
             number_layout[0],
         );
         f.render_widget(
             number_paragraph(format!(
-                "Incorrectly typed words: {}",
+                "Wrong words: {}",
                 stats.total_words - stats.correct_words
             )),
             number_layout[1],
@@ -94,72 +155,270 @@ impl Component for StatComp {
             )),
             number_layout[2],
         );
+        //technique numbers are in the row under speed and accuracy
+        let technique_layout = Layout::horizontal([Constraint::Ratio(1, 3), Constraint::Ratio(1, 3), Constraint::Ratio(1, 3)])
+            .split(Rect {
+                y: number_layout[0].y + 1,
+                height: 1,
+                ..number_area
+            });
+        f.render_widget(
+            number_paragraph(format!("Alternation: {}%", self.fingers.alternation.round())),
+            technique_layout[0],
+        );
+        f.render_widget(
+            number_paragraph(format!("Same finger: {:.1}%", self.fingers.same_finger_bigrams)),
+            technique_layout[1],
+        );
+        f.render_widget(
+            number_paragraph(format!(
+                "Left/right: {}/{}%",
+                self.fingers.left_share.round(),
+                (100.0 - self.fingers.left_share).round()
+            )),
+            technique_layout[2],
+        );
 
-        let graph_zone = zones_layout[1];
-        let width = 110_f64 + 10.0;
-        let height = 110_f64;
-        let canvas = Canvas::default()
-            .block(Block::bordered().title("Graph"))
-            .x_bounds([0.0, width])
-            .y_bounds([0.0, height])
-            .paint(|ctx| {
-                // ctx.draw(&Map {
-                //     resolution: MapResolution::High,
-                //     color: Color::White,
-                // });
-                ctx.layer();
-                let mut speed_stat_scaled: Vec<i32> = Vec::new();
-
-                let block_width = width / stats.speed_stat.len() as f64;
-                //makes vector shorter if too many elements
-                if stats.speed_stat.len() > width as usize {
-                    let scale = stats.speed_stat.len() as f64 / (width / block_width); //scale for stats to fit 20 blocks
-                    let mut count = 0;
-                    let mut sum = 0;
-                    for interval in &stats.speed_stat {
-                        count += 1;
-                        sum += interval;
-                        if ((scale - count as f64) / 10.0).round() * 10.0 == 0.0
-                            || count == stats.speed_stat.len()
-                        {
-                            speed_stat_scaled.push(sum);
-                            sum = 0;
-                            count = 0;
-                        }
-                    }
-                } else {
-                    speed_stat_scaled = stats.speed_stat.iter().map(|x| -> i32 { *x }).collect();
-                }
+        let chart_layout = Layout::horizontal([Constraint::Min(0), Constraint::Length(FINGER_TABLE_WIDTH)]).split(zones_layout[1]);
+        speed_chart(f, chart_layout[0], &stats);
+        finger_table(f, chart_layout[1], &self.fingers);
+    }
+}
 
-                let len = speed_stat_scaled.len();
-                let mut speed_stat_scaled_stabilized: Vec<f64> = Vec::new();
-                let mut sum = 0.0;
-                //makes it more stable showing speed of all time up to the point on graph
-                for (index, interval) in speed_stat_scaled.iter().enumerate() {
-                    sum += *interval as f64;
-                    speed_stat_scaled_stabilized.push(sum / (index as f64 + 1.0))
-                }
-                let speed_stat_scaled = speed_stat_scaled_stabilized;
-                let mut max_interval = *speed_stat_scaled
+///columns taken by table of finger statistics
+const FINGER_TABLE_WIDTH: u16 = 34;
+
+/// draws accuracy and time per key of every finger, fingers that typed nothing are dimmed
+fn finger_table(f: &mut Frame, area: Rect, fingers: &FingerStats) {
+    let mut lines = vec![Line::from(format!("{:<13}{:>9}{:>10}", "finger", "accuracy", "per key")).bold()];
+    for summary in &fingers.fingers {
+        let speed = match summary.ms_per_key() {
+            Some(ms) => format!("{} ms", ms.round()),
+            None => "-".to_string(),
+        };
+        if summary.strokes == 0 {
+            lines.push(Line::from(format!("{:<13}{:>9}{:>10}", summary.finger.name(), "-", "-")).gray());
+        } else {
+            lines.push(Line::from(format!(
+                "{:<13}{:>8}%{:>10}",
+                summary.finger.name(),
+                summary.accuracy().round(),
+                speed
+            )));
+        }
+    }
+    f.render_widget(Paragraph::new(lines).block(Block::bordered().title("Fingers")), area);
+}
+
+///most points drawn on speed chart, longer runs are measured in longer parts
+const MAX_CHART_POINTS: f64 = 120.0;
+
+/// time on chart axis, minutes are shown for long runs
+fn time_label(secs: f64, long: bool) -> String {
+    let secs = secs.round() as u64;
+    if long {
+        format!("{}:{:02}", secs / 60, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// draws net and raw speed over the game with marks on parts with errors
+fn speed_chart(f: &mut Frame, area: Rect, stats: &GameStat) {
+    let total = stats.duration().as_secs_f64();
+    let bucket = Duration::from_secs((total / MAX_CHART_POINTS).ceil().max(1.0) as u64);
+    let timeline = stats.speed_timeline(bucket);
+    let block = Block::bordered().title("Speed");
+    if timeline.is_empty() {
+        f.render_widget(
+            Paragraph::new("nothing was typed").alignment(Alignment::Center).block(block),
+            area,
+        );
+        return;
+    }
+    let wpm: Vec<(f64, f64)> = timeline.iter().map(|p| (p.second, p.wpm)).collect();
+    let raw: Vec<(f64, f64)> = timeline.iter().map(|p| (p.second, p.raw_wpm)).collect();
+    let errors: Vec<(f64, f64)> = timeline
+        .iter()
+        .filter(|p| p.errors > 0)
+        .map(|p| (p.second, p.raw_wpm))
+        .collect();
+
+    let end = timeline.last().map_or(total, |p| p.second).max(1.0);
+    let long = end >= 120.0;
+    let max = timeline
+        .iter()
+        .map(|p| p.wpm.max(p.raw_wpm))
+        .fold(10.0, f64::max);
+    let max = (max / 10.0).ceil() * 10.0;
+    let chart = Chart::new(vec![
+        Dataset::default()
+            .name("raw")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().gray())
+            .data(&raw),
+        Dataset::default()
+            .name("WPM")
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Line)
+            .style(Style::new().yellow())
+            .data(&wpm),
+        Dataset::default()
+            .name(if bucket.as_secs() == 1 { "errors".to_string() } else { format!("errors ({}s)", bucket.as_secs()) })
+            .marker(Marker::Dot)
+            .graph_type(GraphType::Scatter)
+            .style(Style::new().red())
+            .data(&errors),
+    ])
+    .block(block)
+    .x_axis(
+        Axis::default()
+            .title(if long { "time" } else { "seconds" })
+            .bounds([0.0, end])
+            .labels(
+                [0.0, end / 2.0, end]
                     .iter()
-                    .max_by(|a, b| a.total_cmp(b))
-                    .or(Some(&1.0))
-                    .unwrap();
-                if max_interval == 0.0 {
-                    max_interval = 1.0;
-                }
-                //draws graph
-                for (index, interval) in speed_stat_scaled.into_iter().enumerate() {
-                    ctx.draw(&Rectangle {
-                        x: index as f64 * (width / len as f64),
-                        y: 0.0,
-                        width: width / len as f64,
-                        height: (height / max_interval as f64) * interval as f64,
-                        color: Color::White,
-                    });
-                }
+                    .map(|secs| Span::from(time_label(*secs, long)))
+                    .collect(),
+            ),
+    )
+    .y_axis(
+        Axis::default()
+            .title("WPM")
+            .bounds([0.0, max])
+            .labels(vec![Span::from("0"), Span::from(format!("{}", max / 2.0)), Span::from(format!("{}", max))]),
+    )
+    .hidden_legend_constraints((Constraint::Ratio(1, 1), Constraint::Ratio(1, 1)));
+    f.render_widget(chart, area);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+    use std::time::UNIX_EPOCH;
+
+    use crate::game::{GameConf, Limit};
+    use crate::headless::{assert_window_snapshots, HeadlessRunner, TestDir};
+    use crate::layout;
+    use crate::model::Model;
+
+    /// game played with speed slowly growing, every seventh key is wrong
+    fn played_game(duration: Duration) -> Game {
+        let conf = GameConf {
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        let text = "the quick brown fox jumps over the lazy dog ".repeat(200);
+        let mut game = Game::from_text(conf, text.trim_end());
+        game.statistics.time_started = UNIX_EPOCH;
+        let mut at = Duration::ZERO;
+        let mut i = 0;
+        while at < duration {
+            //stopped clock gives every key the time it is pressed at
+            game.statistics.paused_at = Some(UNIX_EPOCH + at);
+            match game.next_char() {
+                Some(_) if i % 7 == 6 => game.char_key_pressed('x'),
+                Some(c) => game.char_key_pressed(c),
+                None => break,
+            }
+            at += Duration::from_millis(250 - (at.as_secs() * 150 / duration.as_secs().max(1)));
+            i += 1;
+        }
+        game
+    }
+
+    fn stat_window_lasting(duration: Duration) -> WindowType {
+        let mut game = played_game(duration);
+        let statistics = GameStat {
+            time_finished: UNIX_EPOCH + duration,
+            ..game.get_statistics()
+        };
+        let fingers = FingerStats::new(&statistics.keystrokes, &layout::bundled()[0]);
+        WindowType::Statistics(StatComp {
+            game,
+            statistics: Some(statistics),
+            personal_best: None,
+            status: None,
+            fingers,
+            keymap: KeyMap::default(),
+        })
+    }
+
+    fn stat_window() -> WindowType {
+        stat_window_lasting(Duration::from_secs(60))
+    }
+
+    #[test]
+    fn personal_best_is_shown() {
+        let dir = TestDir::new("personal_best_is_shown");
+        let settings = dir.settings();
+        let mut history = crate::history::History::new(&settings.paths.history);
+        let mut game = Game::from_text(settings.gameplay.game_conf.clone(), "aa aa");
+        let statistics = game.get_statistics();
+        let mut record = RunRecord::new(&game, &statistics);
+        record.wpm = 70.0;
+        record.accuracy = 96.0;
+        history.add(record);
+
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 120, 30);
+        runner.key(KeyCode::Enter);
+        assert!(runner.screen().contains("personal best: 70 WPM, 96% accuracy"));
+        runner.key(KeyCode::Down).key(KeyCode::Right);
+        assert!(runner.screen().contains("no personal best yet"));
+
+        let mut window = stat_window();
+        if let WindowType::Statistics(comp) = &mut window {
+            comp.personal_best = Some(PersonalBest {
+                previous_wpm: Some(70.0),
+                wpm: 75.0,
             });
+        }
+        runner.model.active_window = window;
+        assert!(runner.screen().contains("New personal best! +5.0 WPM"));
+    }
+
+    #[test]
+    fn speed_chart_scales_with_run_length() {
+        let dir = TestDir::new("speed_chart_scales_with_run_length");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 100, 30);
+        runner.model.active_window = stat_window_lasting(Duration::from_secs(5));
+        let screen = runner.screen();
+        assert!(screen.contains("seconds") && screen.contains("5s"));
+        runner.model.active_window = stat_window_lasting(Duration::from_secs(600));
+        let screen = runner.screen();
+        assert!(screen.contains("5:00") && screen.contains("10:00"));
+        assert!(screen.contains("errors (5s)"));
+    }
+
+    #[test]
+    fn run_is_exported_from_statistics() {
+        let dir = TestDir::new("run_is_exported_from_statistics");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 120, 30);
+        runner.model.active_window = stat_window();
+        runner.key(KeyCode::Char('e'));
+        assert!(runner.screen().contains("Exported to"));
+        let csv = fs::read_to_string(dir.path.join("exports").join("run_60.csv")).unwrap();
+        let records = crate::export::from_csv(&csv).unwrap();
+        assert_eq!(records.len(), 1);
+        assert!(records[0].typed_text.starts_with("the quxck brox"));
+        assert!(dir.path.join("exports").join("run_60.json").exists());
+    }
+
+    #[test]
+    fn numbers_fit_minimal_size() {
+        let dir = TestDir::new("numbers_fit_minimal_size");
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 80, 12);
+        runner.model.active_window = stat_window();
+        let screen = runner.screen();
+        for number in ["WPM: 24 ", " Wrong words: 50 ", " Accuracy: 86%", " Same finger: 15.0% ", " Left/right: 54/46%"] {
+            assert!(screen.contains(number), "{number:?} is cut in\n{screen}");
+        }
+    }
 
-        f.render_widget(canvas, graph_zone)
+    #[test]
+    fn window_snapshots() {
+        assert_window_snapshots("statistics", stat_window());
     }
 }
@@ -1,20 +1,308 @@
-use std::{fs, io::Write};
-///read text used for generation of markov chain
-pub fn read_markov_text_file() ->  String {
-    if let Ok(text) =  fs::read_to_string("text_gen.txt"){
-        text
-    }else{
-        let file_in_superposition = fs::File::create_new("text_gen.txt");
-        match file_in_superposition {
-            Ok(mut file) => {
-                if let Err(_) = file.write(DEFAULT_TEXT.as_bytes()){
-                    return "error error".to_string()
-                }
-            },
-            Err(_) => {return "error error".to_string()},
-        }
-        read_markov_text_file() //? possible infinite loop
-    }
-}
-
-const DEFAULT_TEXT: &str = "Mordhau is a multiplayer medieval hack and slash game that has captivated players with its intense and immersive gameplay Developed by Triternion and released in 2019 the game has established a dedicated fanbase thanks to its intricate combat system large scale battles and impressive graphics The gameplay in Mordhau revolves around melee combat with a variety of weapons including swords axes and spears Players can also use ranged weapons like bows and crossbows as well as siege weapons such as catapults and ballistae The combat system is designed to be skill based with a focus on timing precision and strategy This makes the learning curve steep but also ensures that skilled players are rewarded for their efforts One of the standout features of Mordhau is its freeform melee combat system which allows players to control the direction and angle of their attacks and blocks This system is highly nuanced and gives players a lot of creative freedom in how they approach combat For example players can perform overhead swings horizontal slashes and thrusts each with varying degrees of effectiveness depending on the situation Blocking and parrying are also critical skills that require precise timing to be effective In addition to its combat system Mordhau offers a variety of game modes that cater to different playstyles and preferences Frontline is one of the most popular modes pitting two teams against each other in large scale battles where players must capture and hold strategic points on the map Another popular mode is Battle Royale where players fight to be the last one standing in a shrinking play area Horde mode allows players to team up and fight waves of increasingly difficult AI controlled enemies Invasion mode is another large scale mode where one team attacks while the other defends Players can also engage in smaller scale duels and skirmishes which offer a more focused and intense combat experience The customization options in Mordhau are extensive allowing players to create unique characters with a wide range of armor and weapon choices Players can mix and match different pieces of armor to create a look that suits their personal style and preferences The game also features a detailed character creator where players can adjust facial features body types and other attributes to create a truly unique warrior Additionally Mordhau includes a progression system where players can earn in game currency and experience points by participating in matches These can be used to unlock new weapons armor and cosmetic items enhancing the sense of achievement and progression The maps in Mordhau are diverse and meticulously designed ranging from open fields and dense forests to castles and fortresses Each map offers different tactical opportunities and challenges requiring players to adapt their strategies accordingly Some maps are designed for large scale battles with multiple capture points while others are more suited for smaller skirmishes and duels The environmental detail and realism in the maps contribute to the overall immersion of the game making each battle feel like a real medieval conflict The community aspect of Mordhau is also significant with players forming clans and groups to compete in organized matches and tournaments The game supports both official and community run servers giving players a variety of options for how they want to play and interact with others Triternion has also been active in supporting the game with regular updates and patches that address balance issues introduce new content and improve the overall gameplay experience This ongoing support has helped maintain a vibrant and engaged player base Despite its many strengths Mordhau is not without its challenges One of the primary criticisms of the game is its steep learning curve which can be daunting for new players The complexity of the combat system means that it can take a significant amount of time and practice to become proficient This can be discouraging for those who are looking for a more casual gaming experience Additionally while the game offers a variety of modes and maps some players feel that there could be more content and variety to keep the gameplay fresh and exciting over the long term Another issue that has been raised by the community is the presence of toxic behavior and harassment in online matches Like many multiplayer games Mordhau has had to deal with instances of griefing and unsportsmanlike conduct which can negatively impact the experience for some players Triternion has implemented measures to address these issues including reporting and banning systems but it remains an ongoing challenge to maintain a positive and inclusive community Overall Mordhau is a game that offers a deeply rewarding and immersive medieval combat experience Its intricate combat system large scale battles and extensive customization options make it a standout title in the genre While it does have its challenges particularly for new players its strengths in gameplay and community support make it a game worth investing time in Whether you are a fan of medieval history and combat or simply looking for a game that offers a high level of skill and strategy Mordhau has something to offer The games graphics and sound design are also noteworthy adding to the overall immersive experience The visual details of the armor weapons and environments are impressive creating a sense of realism that draws players into the medieval world The sound effects from the clash of swords to the thud of arrows hitting their targets further enhance the intensity of the battles The soundtrack is appropriately epic adding to the atmosphere and excitement of the game Mordhau also features modding support allowing the community to create and share custom content This has led to a wealth of user generated content including new maps game modes and cosmetic items The modding community has been very active and creative contributing to the longevity and replayability of the game Triternion has encouraged this aspect of the game providing tools and resources for modders to work with This has helped to keep the game fresh and engaging as players can continually discover and try out new content In terms of technical performance Mordhau runs smoothly on a variety of hardware configurations The developers have done a good job optimizing the game to ensure that it is accessible to players with different system specifications While there are occasional bugs and performance issues Triternion has been responsive in addressing these problems through regular patches and updates This commitment to improving the game has been appreciated by the player community and has contributed to the games positive reception Looking to the future Mordhau has the potential to continue growing and evolving as Triternion and the community work together to develop new content and features The developers have outlined plans for future updates including new maps weapons and game modes as well as improvements to the existing systems This ongoing development is a positive sign that Mordhau will remain a relevant and exciting game for years to come The competitive scene in Mordhau is another aspect that has gained traction over time With its skill based combat and strategic depth the game is well suited for competitive play Various tournaments and leagues have been organized by the community and Triternion itself offering players a platform to showcase their skills and compete for recognition and prizes This competitive aspect adds another layer of engagement to the game appealing to players who enjoy the thrill of high stakes matches and the camaraderie of team play In conclusion Mordhau is a game that stands out in the crowded field of multiplayer action games with its unique blend of skill based combat extensive customization options and large scale battles It offers a rich and immersive medieval experience that can be deeply rewarding for those willing to invest the time to master its complexities While it has its challenges particularly in terms of its learning curve and community management the ongoing support and development from Triternion and the active involvement of the player community bode well for its future Whether you are a seasoned veteran of melee combat games or a newcomer looking for a challenging and engaging experience Mordhau has something to offer and is well worth exploring";
\ No newline at end of file
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{GameConf, Limit};
+use crate::keymap::KeyMap;
+use crate::layout;
+
+/// name of settings file, it is kept in profile folder
+pub const SETTINGS_PATH: &str = "settings.toml";
+
+/// user preferences stored in settings file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    ///appearance
+    pub hud: HudConf,
+    pub gameplay: GameplayConf,
+    pub input: InputConf,
+    pub sound: SoundConf,
+    pub paths: PathsConf,
+}
+impl Settings {
+    /// checks if settings can be used, returns description of the problem if not
+    pub fn validate(&self) -> Result<(), String> {
+        match self.gameplay.game_conf.limit {
+            Limit::Time(t) if !(1..=600).contains(&t.as_secs()) => {
+                return Err("time limit should be from 1 to 600 seconds".to_string())
+            }
+            Limit::WordCount(wc) if !(1..=10000).contains(&wc) => {
+                return Err("word limit should be from 1 to 10000 words".to_string())
+            }
+            _ => (),
+        }
+        for dir in [&self.paths.languages, &self.paths.layouts] {
+            if fs::metadata(dir).is_ok_and(|m| !m.is_dir()) {
+                return Err(format!("{} is not a folder", dir));
+            }
+        }
+        for (file, what) in [(&self.paths.history, "history"), (&self.paths.game_confs, "game configurations")] {
+            let dir = Path::new(file).parent().unwrap_or(Path::new(""));
+            if file.is_empty() || !(dir.as_os_str().is_empty() || dir.is_dir()) {
+                return Err(format!("can't create {} file {}", what, file));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// what is shown in game HUD and where
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HudConf {
+    pub show_wpm: bool,
+    pub show_raw_wpm: bool,
+    pub show_accuracy: bool,
+    pub show_progress: bool,
+    pub placement: HudPlacement,
+    ///on-screen keyboard under typed text, it is always shown while layout is emulated
+    pub show_keyboard: bool,
+}
+impl Default for HudConf {
+    fn default() -> Self {
+        HudConf {
+            show_wpm: true,
+            show_raw_wpm: true,
+            show_accuracy: true,
+            show_progress: true,
+            placement: HudPlacement::Top,
+            show_keyboard: false,
+        }
+    }
+}
+
+/// where HUD is rendered in game window
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HudPlacement {
+    Top,
+    Bottom,
+    Hidden,
+}
+
+/// defaults for new games
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameplayConf {
+    ///configuration game configuration window starts with
+    pub game_conf: GameConf,
+}
+impl Default for GameplayConf {
+    fn default() -> Self {
+        GameplayConf {
+            game_conf: GameConf::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputConf {
+    pub keymap: KeyMap,
+    ///id of keyboard layout the player types on
+    pub layout: String,
+    ///layout pressed keys are turned into, `None` if keys type what the layout says
+    pub emulated_layout: Option<String>,
+}
+impl Default for InputConf {
+    fn default() -> Self {
+        InputConf {
+            keymap: KeyMap::default(),
+            layout: layout::DEFAULT_LAYOUT.to_string(),
+            emulated_layout: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SoundConf {
+    ///ring terminal bell on wrong letter
+    pub bell_on_error: bool,
+    ///ring terminal bell when game is finished
+    pub bell_on_finish: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PathsConf {
+    ///folder with language packs added by user
+    pub languages: String,
+    ///folder with keyboard layouts added by user
+    pub layouts: String,
+    ///results of finished games
+    pub history: String,
+    ///last used game configuration and presets
+    pub game_confs: String,
+    ///folder for runs exported from statistics window
+    pub exports: String,
+    ///text for generation of older versions, it is imported as user language pack and not written back
+    #[serde(skip_serializing)]
+    pub markov_text: Option<String>,
+}
+impl Default for PathsConf {
+    fn default() -> Self {
+        PathsConf {
+            languages: "languages".to_string(),
+            layouts: "layouts".to_string(),
+            history: "history.jsonl".to_string(),
+            game_confs: "game_confs.toml".to_string(),
+            exports: "exports".to_string(),
+            markov_text: None,
+        }
+    }
+}
+
+///read settings file, creates it with default settings if it doesn't exist,
+///also returns the problem if the file is broken and defaults are used
+pub fn read_settings(path: &str) -> (Settings, Option<String>) {
+    match fs::read_to_string(path) {
+        Ok(text) => match toml::from_str::<Settings>(&text) {
+            Ok(mut settings) => {
+                settings.input.keymap.bind_missing();
+                (settings, None)
+            }
+            Err(e) => (Settings::default(), Some(keep_broken(path, &text, &e))), //broken file shouldn't stop the game
+        },
+        Err(_) => {
+            let settings = Settings::default();
+            if let Ok(text) = toml::to_string(&settings) {
+                let _ = fs::write(path, text);
+            }
+            (settings, None)
+        }
+    }
+}
+
+/// copies file that can't be parsed next to it, so saving defaults doesn't destroy it,
+/// returns message for the player
+fn keep_broken(path: &str, text: &str, e: &toml::de::Error) -> String {
+    let error = match e.span() {
+        Some(span) => format!("line {}: {}", text[..span.start].matches('\n').count() + 1, e.message()),
+        None => e.message().to_string(),
+    };
+    let backup = format!("{}.broken", path);
+    match fs::write(&backup, text) {
+        Ok(()) => format!("{} is broken ({}), defaults are used, the file is kept as {}", path, error, backup),
+        Err(backup_error) => format!("{} is broken ({}), defaults are used, can't keep it: {}", path, error, backup_error),
+    }
+}
+
+///writes settings to settings file
+pub fn save_settings(path: &str, settings: &Settings) -> Result<(), String> {
+    let text = toml::to_string(settings).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| e.to_string())
+}
+
+/// game configurations remembered between sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameConfStore {
+    ///configuration of the last started game
+    pub last: Option<GameConf>,
+    pub presets: Vec<GameConfPreset>,
+}
+
+/// game configuration saved by user under a name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameConfPreset {
+    pub name: String,
+    pub game_conf: GameConf,
+}
+
+///read remembered game configurations, empty store if there is no file,
+///also returns the problem if the file is broken and empty store is used
+pub fn read_game_conf_store(path: &str) -> (GameConfStore, Option<String>) {
+    match fs::read_to_string(path) {
+        Ok(text) => match toml::from_str(&text) {
+            Ok(store) => (store, None),
+            Err(e) => (GameConfStore::default(), Some(keep_broken(path, &text, &e))),
+        },
+        Err(_) => (GameConfStore::default(), None),
+    }
+}
+
+///writes remembered game configurations to file
+pub fn save_game_conf_store(path: &str, store: &GameConfStore) -> Result<(), String> {
+    let text = toml::to_string(store).map_err(|e| e.to_string())?;
+    fs::write(path, text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{Strictness, TextSource};
+    use std::time::Duration;
+    #[test]
+    fn settings_round_trip() {
+        let mut settings = Settings::default();
+        for limit in [Limit::Time(Duration::from_secs(45)), Limit::WordCount(20), Limit::None] {
+            settings.gameplay.game_conf.limit = limit.clone();
+            let text = toml::to_string(&settings).unwrap();
+            let read: Settings = toml::from_str(&text).unwrap();
+            assert_eq!(read.gameplay.game_conf.limit, limit);
+        }
+    }
+
+    #[test]
+    fn removed_settings_are_ignored() {
+        let read: Settings = toml::from_str("[gameplay]\nstat_update_period_ms = 500\n").unwrap();
+        assert_eq!(read.gameplay.game_conf, GameConf::new());
+    }
+
+    #[test]
+    fn game_conf_store_round_trip() {
+        let store = GameConfStore {
+            last: Some(GameConf {
+                text_source: TextSource::File("my text.txt".to_string()),
+                strictness: Strictness::Strict,
+                ..GameConf::new()
+            }),
+            presets: vec![GameConfPreset {
+                name: "sprint".to_string(),
+                game_conf: GameConf {
+                    limit: Limit::WordCount(10),
+                    ..GameConf::new()
+                },
+            }],
+        };
+        let read: GameConfStore = toml::from_str(&toml::to_string(&store).unwrap()).unwrap();
+        assert_eq!(read.last, store.last);
+        assert_eq!(read.presets, store.presets);
+    }
+
+    #[test]
+    fn broken_files_are_kept() {
+        let dir = std::env::temp_dir().join(format!("typing_game_broken_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.toml").to_string_lossy().to_string();
+        fs::write(&path, "[hud]\nshow_wpm = true\nshow_raw_wpm = yes\n").unwrap();
+        let (settings, problem) = read_settings(&path);
+        assert_eq!(settings.hud.show_raw_wpm, HudConf::default().show_raw_wpm);
+        let problem = problem.unwrap();
+        assert!(problem.contains("line 3"), "{}", problem);
+        assert!(problem.contains("settings.toml.broken"));
+        assert_eq!(fs::read_to_string(format!("{}.broken", path)).unwrap(), fs::read_to_string(&path).unwrap());
+
+        let path = dir.join("game_confs.toml").to_string_lossy().to_string();
+        fs::write(&path, "presets = 5").unwrap();
+        let (store, problem) = read_game_conf_store(&path);
+        assert!(store.presets.is_empty());
+        assert!(problem.unwrap().contains("game_confs.toml is broken"));
+        assert!(Path::new(&format!("{}.broken", path)).exists());
+        assert_eq!(read_game_conf_store(&dir.join("missing.toml").to_string_lossy()).1, None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn validate_limits() {
+        let mut settings = Settings::default();
+        assert!(settings.validate().is_ok());
+        settings.gameplay.game_conf.limit = Limit::Time(Duration::from_secs(0));
+        assert!(settings.validate().is_err());
+        settings.gameplay.game_conf.limit = Limit::WordCount(10);
+        assert!(settings.validate().is_ok());
+        settings.paths.languages = "Cargo.toml".to_string();
+        assert!(settings.validate().is_err());
+    }
+}
@@ -0,0 +1,314 @@
+//! Export of finished runs to CSV and JSON
+//!
+//! JSON file is an array of run records, the same objects that are written
+//! to history file one per line:
+//! `finished_at` (unix seconds), `game_conf` (`mode`, `limit`, `text_source`, `strictness`, `language`),
+//! `duration_secs`, `wpm`, `raw_wpm`, `accuracy` (percent), `correct_words`, `total_words`,
+//! `correct_strokes`, `wrong_strokes`, `cheat_flags` (list of names), `typed_text` and
+//! `origin` (`Local`, `Monkeytype` or `Typeracer`).
+//!
+//! CSV file has a header row and one row per run with columns:
+//!
+//! | column          | value                                                   |
+//! |-----------------|---------------------------------------------------------|
+//! | finished_at     | unix time in seconds                                    |
+//! | mode            | `normal` or `rewrite`                                   |
+//! | limit_type      | `time`, `words` or `none`                               |
+//! | limit_value     | seconds for `time`, word count for `words`, 0 for `none` |
+//! | text_source     | `generated` or path of the text file                    |
+//! | language        | id of language pack of generated text, `en` if missing  |
+//! | strictness      | `normal` or `strict`                                    |
+//! | duration_secs   | time spent typing                                       |
+//! | wpm, raw_wpm    | speed with correct words only and with every letter     |
+//! | accuracy        | percent of correct keystrokes                           |
+//! | correct_words, total_words, correct_strokes, wrong_strokes | counts       |
+//! | cheat_flags     | names of flags separated by `;`, empty for valid runs   |
+//! | typed_text      | letters typed by player                                 |
+//! | origin          | `Local`, `Monkeytype` or `Typeracer`, `Local` if missing |
+
+use std::{fs, path::Path, time::Duration};
+
+use serde::{Deserialize, Serialize};
+use typing_engine::CheatFlag;
+
+use crate::game::{GameConf, GameMode, Limit, Strictness, TextSource};
+use crate::history::{Origin, RunRecord};
+use crate::language;
+
+/// file format of export, chosen by file extension
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+}
+impl ExportFormat {
+    /// format for the file extension, `None` if extension is unknown
+    pub fn from_path(path: &str) -> Option<ExportFormat> {
+        match Path::new(path).extension()?.to_str()?.to_lowercase().as_str() {
+            "csv" => Some(ExportFormat::Csv),
+            "json" => Some(ExportFormat::Json),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+}
+
+/// one line of CSV export, see module documentation
+#[derive(Debug, Serialize, Deserialize)]
+struct CsvRow {
+    finished_at: u64,
+    mode: String,
+    limit_type: String,
+    limit_value: f64,
+    text_source: String,
+    #[serde(default = "default_language")]
+    language: String,
+    strictness: String,
+    duration_secs: f64,
+    wpm: f64,
+    raw_wpm: f64,
+    accuracy: f64,
+    correct_words: u32,
+    total_words: u32,
+    correct_strokes: u32,
+    wrong_strokes: u32,
+    cheat_flags: String,
+    typed_text: String,
+    #[serde(default)]
+    origin: Origin,
+}
+impl CsvRow {
+    fn new(record: &RunRecord) -> CsvRow {
+        let conf = &record.game_conf;
+        let (limit_type, limit_value) = match &conf.limit {
+            Limit::Time(t) => ("time", t.as_secs_f64()),
+            Limit::WordCount(wc) => ("words", *wc as f64),
+            Limit::None => ("none", 0.0),
+        };
+        let flags: Vec<String> = record.cheat_flags.iter().map(flag_name).collect();
+        CsvRow {
+            finished_at: record.finished_at,
+            mode: match conf.mode {
+                GameMode::Normal => "normal",
+                GameMode::Rewrite => "rewrite",
+            }
+            .to_string(),
+            limit_type: limit_type.to_string(),
+            limit_value,
+            text_source: match &conf.text_source {
+                TextSource::Generated => "generated".to_string(),
+                TextSource::File(path) => path.clone(),
+            },
+            language: conf.language.clone(),
+            strictness: match conf.strictness {
+                Strictness::Normal => "normal",
+                Strictness::Strict => "strict",
+            }
+            .to_string(),
+            duration_secs: record.duration_secs,
+            wpm: record.wpm,
+            raw_wpm: record.raw_wpm,
+            accuracy: record.accuracy,
+            correct_words: record.correct_words,
+            total_words: record.total_words,
+            correct_strokes: record.correct_strokes,
+            wrong_strokes: record.wrong_strokes,
+            cheat_flags: flags.join(";"),
+            typed_text: record.typed_text.clone(),
+            origin: record.origin,
+        }
+    }
+
+    fn into_record(self) -> Result<RunRecord, String> {
+        let mode = match self.mode.as_str() {
+            "normal" => GameMode::Normal,
+            "rewrite" => GameMode::Rewrite,
+            other => return Err(format!("unknown mode {}", other)),
+        };
+        let limit = match self.limit_type.as_str() {
+            "time" => Limit::Time(Duration::from_secs_f64(self.limit_value.max(0.0))),
+            "words" => Limit::WordCount(self.limit_value as u32),
+            "none" => Limit::None,
+            other => return Err(format!("unknown limit {}", other)),
+        };
+        let strictness = match self.strictness.as_str() {
+            "normal" => Strictness::Normal,
+            "strict" => Strictness::Strict,
+            other => return Err(format!("unknown strictness {}", other)),
+        };
+        let cheat_flags = self
+            .cheat_flags
+            .split(';')
+            .filter(|name| !name.is_empty())
+            .map(flag_from_name)
+            .collect::<Result<Vec<CheatFlag>, String>>()?;
+        Ok(RunRecord {
+            finished_at: self.finished_at,
+            game_conf: GameConf {
+                mode,
+                limit,
+                text_source: match self.text_source.as_str() {
+                    "generated" => TextSource::Generated,
+                    path => TextSource::File(path.to_string()),
+                },
+                strictness,
+                language: self.language,
+            },
+            duration_secs: self.duration_secs,
+            wpm: self.wpm,
+            raw_wpm: self.raw_wpm,
+            accuracy: self.accuracy,
+            correct_words: self.correct_words,
+            total_words: self.total_words,
+            correct_strokes: self.correct_strokes,
+            wrong_strokes: self.wrong_strokes,
+            cheat_flags,
+            typed_text: self.typed_text,
+            origin: self.origin,
+        })
+    }
+}
+
+fn default_language() -> String {
+    language::DEFAULT_LANGUAGE.to_string()
+}
+
+/// name of the flag as it is written in history file
+fn flag_name(flag: &CheatFlag) -> String {
+    match serde_json::to_value(flag) {
+        Ok(serde_json::Value::String(name)) => name,
+        _ => format!("{:?}", flag),
+    }
+}
+
+fn flag_from_name(name: &str) -> Result<CheatFlag, String> {
+    serde_json::from_value(serde_json::Value::String(name.to_string())).map_err(|_| format!("unknown flag {}", name))
+}
+
+pub fn to_csv(records: &[RunRecord]) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for record in records {
+        writer.serialize(CsvRow::new(record)).map_err(|e| e.to_string())?;
+    }
+    let bytes = writer.into_inner().map_err(|e| e.to_string())?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+pub fn from_csv(text: &str) -> Result<Vec<RunRecord>, String> {
+    csv::Reader::from_reader(text.as_bytes())
+        .deserialize::<CsvRow>()
+        .map(|row| row.map_err(|e| e.to_string())?.into_record())
+        .collect()
+}
+
+pub fn to_json(records: &[RunRecord]) -> Result<String, String> {
+    serde_json::to_string_pretty(records).map_err(|e| e.to_string())
+}
+
+pub fn from_json(text: &str) -> Result<Vec<RunRecord>, String> {
+    serde_json::from_str(text).map_err(|e| e.to_string())
+}
+
+/// writes records to file, format is chosen by extension
+pub fn write(path: &str, records: &[RunRecord]) -> Result<(), String> {
+    let text = match ExportFormat::from_path(path) {
+        Some(ExportFormat::Csv) => to_csv(records)?,
+        Some(ExportFormat::Json) => to_json(records)?,
+        None => return Err(format!("{} should end with .csv or .json", path)),
+    };
+    if let Some(dir) = Path::new(path).parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+    }
+    fs::write(path, text).map_err(|e| e.to_string())
+}
+
+/// writes one run to folder as both CSV and JSON, returns paths of written files
+pub fn write_run(dir: &str, record: &RunRecord) -> Result<Vec<String>, String> {
+    [ExportFormat::Csv, ExportFormat::Json]
+        .iter()
+        .map(|format| {
+            let path = Path::new(dir)
+                .join(format!("run_{}.{}", record.finished_at, format.extension()))
+                .to_string_lossy()
+                .to_string();
+            write(&path, std::slice::from_ref(record)).map(|_| path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    fn records() -> Vec<RunRecord> {
+        let record = |limit: Limit, text_source: TextSource, cheat_flags: Vec<CheatFlag>| RunRecord {
+            finished_at: 1717243200,
+            game_conf: GameConf {
+                mode: GameMode::Rewrite,
+                limit,
+                text_source,
+                strictness: Strictness::Strict,
+                language: "pl".to_string(),
+            },
+            duration_secs: 31.25,
+            wpm: 61.44,
+            raw_wpm: 70.1,
+            accuracy: 96.5,
+            correct_words: 32,
+            total_words: 33,
+            correct_strokes: 170,
+            wrong_strokes: 6,
+            cheat_flags,
+            typed_text: "some \"quoted\", text\nwith ł".to_string(),
+            origin: Origin::Local,
+        };
+        vec![
+            record(Limit::Time(Duration::from_secs(30)), TextSource::Generated, vec![]),
+            record(
+                Limit::WordCount(25),
+                TextSource::File("texts/a, b.txt".to_string()),
+                vec![CheatFlag::Paste, CheatFlag::KeyRepeat],
+            ),
+            RunRecord {
+                origin: Origin::Typeracer,
+                ..record(Limit::None, TextSource::Generated, vec![CheatFlag::SuperhumanSpeed])
+            },
+        ]
+    }
+
+    #[test]
+    fn csv_round_trip() {
+        let records = records();
+        let text = to_csv(&records).unwrap();
+        assert!(text.starts_with("finished_at,mode,limit_type,limit_value,"));
+        assert!(text.contains("Paste;KeyRepeat"));
+        assert_eq!(from_csv(&text).unwrap(), records);
+        assert!(from_csv(&text.replace("rewrite", "sideways")).is_err());
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let records = records();
+        assert_eq!(from_json(&to_json(&records).unwrap()).unwrap(), records);
+    }
+
+    #[test]
+    fn run_is_written_to_folder() {
+        let dir = env::temp_dir().join(format!("typing_game_export_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let record = records().remove(1);
+        let paths = write_run(dir.to_str().unwrap(), &record).unwrap();
+        assert!(paths[0].ends_with("run_1717243200.csv"));
+        assert_eq!(from_csv(&fs::read_to_string(&paths[0]).unwrap()).unwrap(), vec![record.clone()]);
+        assert_eq!(from_json(&fs::read_to_string(&paths[1]).unwrap()).unwrap(), vec![record]);
+        assert!(write(dir.join("runs.txt").to_str().unwrap(), &[]).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
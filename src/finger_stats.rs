@@ -0,0 +1,156 @@
+use std::time::Duration;
+
+use typing_engine::Keystroke;
+
+use crate::layout::{Finger, Hand, Key, KeyboardLayout};
+
+/// longer time between keys is a pause, it doesn't count into finger speed
+const MAX_KEY_INTERVAL: Duration = Duration::from_secs(2);
+
+/// keys one finger should have pressed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerSummary {
+    pub finger: Finger,
+    pub strokes: u32,
+    pub correct: u32,
+    ///time since previous key of correct strokes
+    time: Duration,
+    timed: u32,
+}
+impl FingerSummary {
+    fn new(finger: Finger) -> FingerSummary {
+        FingerSummary {
+            finger,
+            strokes: 0,
+            correct: 0,
+            time: Duration::ZERO,
+            timed: 0,
+        }
+    }
+
+    /// percent of correct strokes
+    pub fn accuracy(&self) -> f64 {
+        if self.strokes == 0 {
+            return 0.0;
+        }
+        self.correct as f64 / self.strokes as f64 * 100.0
+    }
+
+    /// average milliseconds from previous key, `None` if finger typed nothing in time
+    pub fn ms_per_key(&self) -> Option<f64> {
+        if self.timed == 0 {
+            return None;
+        }
+        Some(self.time.as_secs_f64() * 1000.0 / self.timed as f64)
+    }
+}
+
+/// typing technique of one run, keys are given to fingers by the char that should be typed
+#[derive(Debug, Clone, PartialEq)]
+pub struct FingerStats {
+    ///every finger in order from left pinky to right pinky
+    pub fingers: Vec<FingerSummary>,
+    ///percent of key pairs typed by different hands, space breaks pairs
+    pub alternation: f64,
+    ///percent of key pairs typed by the same finger on different keys
+    pub same_finger_bigrams: f64,
+    ///percent of keys typed by left hand, the rest is typed by right hand
+    pub left_share: f64,
+}
+impl FingerStats {
+    /// counts statistics from keystrokes, held down keys and chars the layout doesn't have are skipped
+    pub fn new(keystrokes: &[Keystroke], layout: &KeyboardLayout) -> FingerStats {
+        let mut fingers: Vec<FingerSummary> = Finger::ALL.iter().map(|f| FingerSummary::new(*f)).collect();
+        let mut pairs = 0;
+        let mut alternating = 0;
+        let mut same_finger = 0;
+        let mut left = 0;
+        let mut right = 0;
+        let mut previous: Option<(&Keystroke, Key)> = None;
+        for stroke in keystrokes.iter().filter(|s| !s.repeat) {
+            let key = match layout.key(stroke.expected) {
+                Some(key) => key,
+                None => {
+                    previous = None;
+                    continue;
+                }
+            };
+            let finger = key.finger();
+            let summary = fingers.iter_mut().find(|s| s.finger == finger).unwrap();
+            summary.strokes += 1;
+            if stroke.is_correct() {
+                summary.correct += 1;
+                if let Some((prev, _)) = previous {
+                    let interval = stroke.at.saturating_sub(prev.at);
+                    if interval <= MAX_KEY_INTERVAL {
+                        summary.time += interval;
+                        summary.timed += 1;
+                    }
+                }
+            }
+            match finger.hand() {
+                Some(Hand::Left) => left += 1,
+                Some(Hand::Right) => right += 1,
+                None => (),
+            }
+            if let Some((_, prev_key)) = previous {
+                if let (Some(prev_hand), Some(hand)) = (prev_key.finger().hand(), finger.hand()) {
+                    pairs += 1;
+                    if prev_hand != hand {
+                        alternating += 1;
+                    } else if prev_key.finger() == finger && prev_key.pos != key.pos {
+                        same_finger += 1;
+                    }
+                }
+            }
+            previous = Some((stroke, key));
+        }
+        let percent = |part: u32, whole: u32| if whole == 0 { 0.0 } else { part as f64 / whole as f64 * 100.0 };
+        FingerStats {
+            fingers,
+            alternation: percent(alternating, pairs),
+            same_finger_bigrams: percent(same_finger, pairs),
+            left_share: percent(left, left + right),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::layout;
+
+    fn stroke(ms: u64, typed: char, expected: char) -> Keystroke {
+        Keystroke {
+            at: Duration::from_millis(ms),
+            typed,
+            expected,
+            repeat: false,
+        }
+    }
+
+    #[test]
+    fn finger_and_hand_statistics() {
+        let layouts = layout::bundled();
+        let qwerty = layout::find(&layouts, "qwerty").unwrap();
+        let keystrokes = vec![
+            stroke(0, 'f', 'f'),
+            stroke(100, 't', 't'),
+            stroke(200, 'k', 'j'),
+            stroke(300, 'r', 'r'),
+            stroke(5000, 'f', 'f'),
+        ];
+        let stats = FingerStats::new(&keystrokes, qwerty);
+        let finger = |f: Finger| stats.fingers.iter().find(|s| s.finger == f).unwrap();
+        assert_eq!(finger(Finger::LeftIndex).strokes, 4);
+        assert_eq!(finger(Finger::LeftIndex).accuracy(), 100.0);
+        //first key has nothing to be measured from and long pause is skipped
+        assert_eq!(finger(Finger::LeftIndex).ms_per_key(), Some(100.0));
+        assert_eq!(finger(Finger::RightIndex).accuracy(), 0.0);
+        assert_eq!(finger(Finger::RightIndex).ms_per_key(), None);
+        //f-t and r-f are the same finger, t-j and j-r change hands
+        assert_eq!(stats.alternation, 50.0);
+        assert_eq!(stats.same_finger_bigrams, 50.0);
+        assert_eq!(stats.left_share, 80.0);
+    }
+}
@@ -0,0 +1,185 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process, thread,
+    time::SystemTime,
+};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, buffer::Buffer, Terminal};
+
+use crate::component::{Component, WindowType};
+use crate::config_manager::Settings;
+use crate::model::Model;
+use crate::profile::{self, Profile};
+use crate::{dispatch, view, Message};
+
+/// runs the app without real terminal, messages are fed by test script and screen is rendered into memory
+pub struct HeadlessRunner {
+    pub model: Model,
+    terminal: Terminal<TestBackend>,
+}
+impl HeadlessRunner {
+    pub fn new(model: Model, width: u16, height: u16) -> HeadlessRunner {
+        HeadlessRunner {
+            model,
+            terminal: Terminal::new(TestBackend::new(width, height)).unwrap(),
+        }
+    }
+
+    /// updates model with message the same way main loop does
+    pub fn send(&mut self, msg: Message) -> &mut Self {
+        dispatch(&mut self.model, msg);
+        self
+    }
+
+    /// presses key with modifiers
+    pub fn key_with(&mut self, code: KeyCode, modifiers: KeyModifiers) -> &mut Self {
+        self.send(Message::KeyInput(KeyEvent::new(code, modifiers)))
+    }
+
+    /// presses key without modifiers
+    pub fn key(&mut self, code: KeyCode) -> &mut Self {
+        self.key_with(code, KeyModifiers::NONE)
+    }
+
+    /// presses keys for every char of the text
+    pub fn type_text(&mut self, text: &str) -> &mut Self {
+        for c in text.chars() {
+            self.key(KeyCode::Char(c));
+        }
+        self
+    }
+
+    /// sends `count` ticks
+    pub fn tick(&mut self, count: usize) -> &mut Self {
+        for _ in 0..count {
+            self.send(Message::Tick);
+        }
+        self
+    }
+
+    /// sleeps until active window asks for tick and sends it like main loop does,
+    /// returns false if no tick is scheduled
+    pub fn wait_tick(&mut self) -> bool {
+        match self.model.active_window.next_tick() {
+            Some(at) => {
+                thread::sleep(at.duration_since(SystemTime::now()).unwrap_or_default());
+                self.send(Message::Tick);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// changes size of the screen, next render uses new size
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.terminal.backend_mut().resize(width, height);
+        self
+    }
+
+    /// renders active window and returns rendered cells
+    pub fn render(&mut self) -> &Buffer {
+        let model = &mut self.model;
+        self.terminal.draw(|f| view(model, f)).unwrap();
+        self.terminal.backend().buffer()
+    }
+
+    /// renders active window and returns it as text, one line per row
+    pub fn screen(&mut self) -> String {
+        let buffer = self.render();
+        let area = buffer.area;
+        let mut text = String::new();
+        for y in 0..area.height {
+            for x in 0..area.width {
+                text.push_str(buffer.get(x, y).symbol());
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// compares screen with snapshot saved in src/snapshots,
+/// snapshot is written instead if it doesn't exist or UPDATE_SNAPSHOTS is set
+pub fn assert_snapshot(name: &str, screen: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/snapshots").join(format!("{}.txt", name));
+    match fs::read_to_string(&path) {
+        Ok(saved) if env::var_os("UPDATE_SNAPSHOTS").is_none() => {
+            assert!(saved == screen, "screen doesn't match snapshot {}\nexpected:\n{}\nfound:\n{}", name, saved, screen)
+        }
+        _ => {
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, screen).unwrap();
+        }
+    }
+}
+
+/// sizes every window is compared with snapshots in
+const SNAPSHOT_SIZES: [(u16, u16); 4] = [(20, 6), (40, 12), (80, 24), (120, 40)];
+
+/// compares window in every snapshot size with snapshots `<name>_<width>x<height>`,
+/// then renders it in sizes around minimal sizes of windows, none of them may panic
+pub fn assert_window_snapshots(name: &str, window: WindowType) {
+    let dir = TestDir::new(&format!("snapshots_{}", name));
+    let mut runner = HeadlessRunner::new(Model::new(dir.profile(), dir.settings()), 1, 1);
+    runner.model.active_window = window;
+    for (width, height) in SNAPSHOT_SIZES {
+        runner.resize(width, height);
+        assert_snapshot(&format!("{}_{}x{}", name, width, height), &runner.screen());
+    }
+    for width in [0, 1, 2, 3, 5, 10, 20, 29, 30, 31, 39, 40, 41, 49, 50, 51, 79, 80, 81, 130] {
+        for height in [0, 1, 2, 3, 5, 8, 9, 10, 11, 12, 13, 19, 20, 21, 24, 45] {
+            runner.resize(width, height);
+            runner.render();
+        }
+    }
+}
+
+/// directory for files of one test, removed when dropped
+pub struct TestDir {
+    pub path: PathBuf,
+}
+impl TestDir {
+    pub fn new(name: &str) -> TestDir {
+        let path = env::temp_dir().join(format!("typing_game_{}_{}", name, process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir { path }
+    }
+
+    /// writes file into directory and returns its path
+    pub fn file(&self, name: &str, text: &str) -> String {
+        let path = self.path.join(name);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).unwrap();
+        }
+        fs::write(&path, text).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    /// profile kept inside this directory
+    pub fn profile(&self) -> Profile {
+        let root = self.path.join("profiles");
+        profile::create(&root, "test").unwrap_or(Profile::new(root, "test"))
+    }
+
+    /// default settings with all files inside this directory
+    pub fn settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        //English pack of the test generates known text
+        self.file("languages/en/corpus.txt", "aa aa aa aa aa aa");
+        settings.paths.languages = self.path.join("languages").to_str().unwrap().to_string();
+        settings.paths.layouts = self.path.join("layouts").to_str().unwrap().to_string();
+        settings.paths.history = self.path.join("history.jsonl").to_str().unwrap().to_string();
+        settings.paths.game_confs = self.path.join("game_confs.toml").to_str().unwrap().to_string();
+        settings.paths.exports = self.path.join("exports").to_str().unwrap().to_string();
+        settings.paths.markov_text = Some(self.path.join("text_gen.txt").to_str().unwrap().to_string());
+        settings
+    }
+}
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
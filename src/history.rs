@@ -0,0 +1,295 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Game, GameConf, GameStat};
+use typing_engine::CheatFlag;
+
+/// program where the run was played
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum Origin {
+    #[default]
+    Local,
+    Monkeytype,
+    Typeracer,
+}
+
+/// result of one finished game as it is stored in history file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    ///unix time in seconds when the game was finished
+    pub finished_at: u64,
+    pub game_conf: GameConf,
+    ///time spent typing in seconds
+    pub duration_secs: f64,
+    pub wpm: f64,
+    pub raw_wpm: f64,
+    pub accuracy: f64,
+    pub correct_words: u32,
+    pub total_words: u32,
+    pub correct_strokes: u32,
+    pub wrong_strokes: u32,
+    ///reasons why the run is invalid, such runs don't count as records
+    #[serde(default)]
+    pub cheat_flags: Vec<CheatFlag>,
+    ///letters typed by player, mistakes included
+    #[serde(default)]
+    pub typed_text: String,
+    ///imported runs keep the name of program they come from
+    #[serde(default)]
+    pub origin: Origin,
+}
+impl RunRecord {
+    pub fn new(game: &Game, stats: &GameStat) -> RunRecord {
+        let duration = stats.duration();
+        let minutes = duration.as_secs_f64() / 60.0;
+        let per_minute = |count: f64| if minutes > 0.0 { count / minutes } else { 0.0 };
+        RunRecord {
+            finished_at: stats
+                .time_finished
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or(Duration::from_secs(0))
+                .as_secs(),
+            game_conf: game.game_conf.clone(),
+            duration_secs: duration.as_secs_f64(),
+            wpm: stats.wpm(),
+            raw_wpm: per_minute(game.written_vec.len() as f64 / 5.0),
+            accuracy: game.get_accuracy(),
+            correct_words: stats.correct_words,
+            total_words: stats.total_words,
+            correct_strokes: stats.correct_strokes,
+            wrong_strokes: stats.wrong_strokes,
+            cheat_flags: stats.cheat_flags.clone(),
+            typed_text: game.written_vec.iter().map(|letter| letter.c.as_str()).collect(),
+            origin: Origin::Local,
+        }
+    }
+
+    /// invalid runs are kept in history but never become personal bests
+    pub fn counts_as_record(&self) -> bool {
+        self.cheat_flags.is_empty()
+    }
+
+    /// imported runs are measured differently so they are shown only on progress charts
+    pub fn counts_as_best(&self) -> bool {
+        self.counts_as_record() && self.origin == Origin::Local
+    }
+}
+
+/// finished run compared with personal best for its configuration
+#[derive(Debug, Clone, PartialEq)]
+pub struct PersonalBest {
+    ///best speed before this run, `None` if it is the first counted run
+    pub previous_wpm: Option<f64>,
+    pub wpm: f64,
+}
+impl PersonalBest {
+    pub fn is_new(&self) -> bool {
+        match self.previous_wpm {
+            Some(previous) => self.wpm > previous,
+            None => true,
+        }
+    }
+
+    /// difference between this run and previous best
+    pub fn delta(&self) -> Option<f64> {
+        self.previous_wpm.map(|previous| self.wpm - previous)
+    }
+}
+
+/// history of finished games, one json record per line
+#[derive(Debug)]
+pub struct History {
+    pub path: String,
+    ///every record from file and this session
+    records: Vec<RunRecord>,
+    ///records not written to file yet
+    pending: Vec<RunRecord>,
+}
+impl History {
+    /// reads records from history file, broken lines are skipped
+    pub fn new(path: &str) -> History {
+        let records = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+        History {
+            path: path.to_string(),
+            records,
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn records(&self) -> &[RunRecord] {
+        &self.records
+    }
+
+    /// best counted run with this configuration
+    pub fn personal_best(&self, conf: &GameConf) -> Option<&RunRecord> {
+        self.records
+            .iter()
+            .filter(|r| r.counts_as_best() && r.game_conf == *conf)
+            .max_by(|a, b| a.wpm.total_cmp(&b.wpm))
+    }
+
+    /// best counted run of every configuration
+    pub fn personal_bests(&self) -> Vec<RunRecord> {
+        let mut bests: Vec<RunRecord> = Vec::new();
+        for record in self.records.iter().filter(|r| r.counts_as_best()) {
+            match bests.iter_mut().find(|b| b.game_conf == record.game_conf) {
+                Some(best) if best.wpm < record.wpm => *best = record.clone(),
+                Some(_) => (),
+                None => bests.push(record.clone()),
+            }
+        }
+        bests
+    }
+
+    /// compares record with personal best, `None` if record doesn't count
+    pub fn compare_with_best(&self, record: &RunRecord) -> Option<PersonalBest> {
+        if !record.counts_as_best() {
+            return None;
+        }
+        Some(PersonalBest {
+            previous_wpm: self.personal_best(&record.game_conf).map(|best| best.wpm),
+            wpm: record.wpm,
+        })
+    }
+
+    /// adds record to history and tries to write it right away
+    pub fn add(&mut self, record: RunRecord) {
+        self.records.push(record.clone());
+        self.pending.push(record);
+        let _ = self.flush(); //records stay pending and are written on next flush if this fails
+    }
+
+    /// adds records that are not in history yet, returns how many were added
+    pub fn import(&mut self, records: Vec<RunRecord>) -> Result<usize, String> {
+        let mut added = 0;
+        for record in records {
+            let known = self.records.iter().any(|r| {
+                r.origin == record.origin && r.finished_at == record.finished_at && r.wpm == record.wpm
+            });
+            if !known {
+                self.records.push(record.clone());
+                self.pending.push(record);
+                added += 1;
+            }
+        }
+        self.flush()?;
+        Ok(added)
+    }
+
+    /// writes pending records to history file
+    pub fn flush(&mut self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let mut text = String::new();
+        for record in &self.pending {
+            text.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+            text.push('\n');
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| e.to_string())?;
+        file.write_all(text.as_bytes()).map_err(|e| e.to_string())?;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{graphemes, Limit};
+    use std::env;
+
+    #[test]
+    fn add_and_read_records() {
+        let path = env::temp_dir().join(format!("typing_game_history_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = History::new(path.to_str().unwrap());
+        let mut game = Game {
+            correct_text: graphemes("cc cc"),
+            written_vec: Vec::new(),
+            pending: String::new(),
+            statistics: GameStat::new(),
+            game_conf: GameConf::new(),
+        };
+        for c in "cc cc".chars() {
+            game.char_key_pressed(c);
+        }
+        let record = RunRecord::new(&game, &game.clone().get_statistics());
+        history.add(record.clone());
+        history.add(record.clone());
+        assert!(history.pending.is_empty());
+        let read: Vec<RunRecord> = fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(read, vec![record.clone(), record]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn personal_bests() {
+        let path = env::temp_dir().join(format!("typing_game_bests_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let mut history = History::new(path.to_str().unwrap());
+        let record = |wpm: f64, limit: Limit, cheat_flags: Vec<CheatFlag>| RunRecord {
+            finished_at: 0,
+            game_conf: GameConf {
+                limit,
+                ..GameConf::new()
+            },
+            duration_secs: 30.0,
+            wpm,
+            raw_wpm: wpm,
+            accuracy: 100.0,
+            correct_words: 0,
+            total_words: 0,
+            correct_strokes: 0,
+            wrong_strokes: 0,
+            cheat_flags,
+            typed_text: String::new(),
+            origin: Origin::Local,
+        };
+        let sprint = Limit::WordCount(10);
+        let first = record(50.0, sprint.clone(), vec![]);
+        assert_eq!(history.compare_with_best(&first).unwrap().delta(), None);
+        history.add(first);
+        history.add(record(70.0, sprint.clone(), vec![]));
+        history.add(record(200.0, sprint.clone(), vec![CheatFlag::Paste]));
+        history.add(record(90.0, Limit::WordCount(20), vec![]));
+
+        //records are read back from file
+        let mut history = History::new(path.to_str().unwrap());
+        assert_eq!(history.personal_best(&record(0.0, sprint.clone(), vec![]).game_conf).unwrap().wpm, 70.0);
+        assert_eq!(history.personal_bests().len(), 2);
+        let better = history.compare_with_best(&record(75.0, sprint.clone(), vec![])).unwrap();
+        assert!(better.is_new());
+        assert_eq!(better.delta(), Some(5.0));
+        assert!(!history.compare_with_best(&record(60.0, sprint.clone(), vec![])).unwrap().is_new());
+        assert!(history.compare_with_best(&record(300.0, sprint.clone(), vec![CheatFlag::KeyRepeat])).is_none());
+
+        //imported runs are added once and don't become personal bests
+        let imported = RunRecord {
+            origin: Origin::Monkeytype,
+            ..record(150.0, sprint.clone(), vec![])
+        };
+        assert_eq!(history.import(vec![imported.clone(), imported.clone()]).unwrap(), 1);
+        assert_eq!(history.import(vec![imported]).unwrap(), 0);
+        assert_eq!(History::new(path.to_str().unwrap()).records().len(), 5);
+        assert_eq!(history.personal_best(&record(0.0, sprint, vec![]).game_conf).unwrap().wpm, 70.0);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,261 @@
+//! Import of results from other typing programs into history
+//!
+//! Supported files:
+//! - monkeytype CSV export (`_id,isPb,wpm,acc,rawWpm,...,charStats,mode,mode2,...,testDuration,...,timestamp`)
+//! - typeracer race log in CSV with `WPM`, `Accuracy` and `Date/Time (UTC)` columns
+//! - CSV and JSON files written by export of this program
+//!
+//! Things other programs don't record, like typed text or word counts, are estimated or left empty.
+
+use std::{fs, time::Duration};
+
+use serde::Deserialize;
+
+use crate::export::{self, ExportFormat};
+use crate::game::{GameConf, Limit};
+use crate::history::{Origin, RunRecord};
+use crate::progress;
+
+/// kind of imported file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportFormat {
+    Monkeytype,
+    Typeracer,
+    ///file written by export
+    Export(ExportFormat),
+}
+impl ImportFormat {
+    /// format given by name on command line
+    pub fn from_name(name: &str) -> Option<ImportFormat> {
+        match name {
+            "monkeytype" => Some(ImportFormat::Monkeytype),
+            "typeracer" => Some(ImportFormat::Typeracer),
+            "csv" => Some(ImportFormat::Export(ExportFormat::Csv)),
+            "json" => Some(ImportFormat::Export(ExportFormat::Json)),
+            _ => None,
+        }
+    }
+
+    /// guesses format by file extension and first line of the file
+    pub fn detect(path: &str, text: &str) -> Option<ImportFormat> {
+        if ExportFormat::from_path(path) == Some(ExportFormat::Json) {
+            return Some(ImportFormat::Export(ExportFormat::Json));
+        }
+        let header = text.lines().next().unwrap_or_default();
+        if header.contains("charStats") || header.contains("rawWpm") {
+            Some(ImportFormat::Monkeytype)
+        } else if header.contains("Date/Time") || header.contains("Race #") {
+            Some(ImportFormat::Typeracer)
+        } else if header.starts_with("finished_at") {
+            Some(ImportFormat::Export(ExportFormat::Csv))
+        } else {
+            None
+        }
+    }
+}
+
+/// records read from file
+#[derive(Debug, PartialEq)]
+pub struct Import {
+    pub records: Vec<RunRecord>,
+    ///lines that couldn't be read
+    pub skipped: usize,
+}
+
+/// one result from monkeytype export, other columns are ignored
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MonkeytypeRow {
+    wpm: f64,
+    acc: f64,
+    raw_wpm: f64,
+    ///"correct;incorrect;extra;missed"
+    #[serde(default)]
+    char_stats: String,
+    mode: String,
+    #[serde(default)]
+    mode2: String,
+    test_duration: f64,
+    ///unix time in milliseconds
+    timestamp: f64,
+}
+impl MonkeytypeRow {
+    fn into_record(self) -> RunRecord {
+        let limit = match (self.mode.as_str(), self.mode2.parse::<u32>()) {
+            ("time", Ok(secs)) => Limit::Time(Duration::from_secs(secs as u64)),
+            ("words", Ok(count)) => Limit::WordCount(count),
+            _ => Limit::None, //quotes, custom texts and zen mode
+        };
+        let chars: Vec<u32> = self.char_stats.split(';').map(|n| n.trim().parse().unwrap_or(0)).collect();
+        let char_stat = |i: usize| chars.get(i).copied().unwrap_or(0);
+        let minutes = self.test_duration / 60.0;
+        RunRecord {
+            finished_at: (self.timestamp / 1000.0 + self.test_duration) as u64,
+            game_conf: GameConf {
+                limit,
+                ..GameConf::new()
+            },
+            duration_secs: self.test_duration,
+            wpm: self.wpm,
+            raw_wpm: self.raw_wpm,
+            accuracy: self.acc,
+            correct_words: (self.wpm * minutes).round() as u32,
+            total_words: (self.raw_wpm * minutes).round() as u32,
+            correct_strokes: char_stat(0),
+            wrong_strokes: char_stat(1) + char_stat(2),
+            cheat_flags: Vec::new(),
+            typed_text: String::new(),
+            origin: Origin::Monkeytype,
+        }
+    }
+}
+
+/// one race from typeracer log, other columns are ignored
+#[derive(Debug, Deserialize)]
+struct TyperacerRow {
+    #[serde(rename = "WPM")]
+    wpm: f64,
+    ///written as "0.97", "97" or "97%"
+    #[serde(rename = "Accuracy")]
+    accuracy: String,
+    #[serde(rename = "Date/Time (UTC)")]
+    date: String,
+}
+impl TyperacerRow {
+    fn into_record(self) -> Option<RunRecord> {
+        let accuracy: f64 = self.accuracy.trim().trim_end_matches('%').parse().ok()?;
+        Some(RunRecord {
+            finished_at: parse_date(&self.date)?,
+            //races are quotes of different length, time is not in the log
+            game_conf: GameConf {
+                limit: Limit::None,
+                ..GameConf::new()
+            },
+            duration_secs: 0.0,
+            wpm: self.wpm,
+            raw_wpm: self.wpm,
+            accuracy: if accuracy <= 1.0 { accuracy * 100.0 } else { accuracy },
+            correct_words: 0,
+            total_words: 0,
+            correct_strokes: 0,
+            wrong_strokes: 0,
+            cheat_flags: Vec::new(),
+            typed_text: String::new(),
+            origin: Origin::Typeracer,
+        })
+    }
+}
+
+/// unix time from date like "2021-03-04 05:06:07" or from unix time itself
+fn parse_date(text: &str) -> Option<u64> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<u64>() {
+        return Some(secs);
+    }
+    let numbers: Vec<i64> = text
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .take(6)
+        .map(|part| part.parse().ok())
+        .collect::<Option<Vec<i64>>>()?;
+    let number = |i: usize| numbers.get(i).copied().unwrap_or(0);
+    if numbers.len() < 3 || !(1..=12).contains(&number(1)) || !(1..=31).contains(&number(2)) {
+        return None;
+    }
+    let day = progress::day_number(number(0), number(1), number(2))?;
+    Some(day * 24 * 60 * 60 + (number(3) * 3600 + number(4) * 60 + number(5)) as u64)
+}
+
+/// reads rows of CSV file, rows that don't fit are counted as skipped
+fn read_rows<T, F>(text: &str, into_record: F) -> Import
+where
+    T: for<'de> Deserialize<'de>,
+    F: Fn(T) -> Option<RunRecord>,
+{
+    let mut import = Import {
+        records: Vec::new(),
+        skipped: 0,
+    };
+    for row in csv::Reader::from_reader(text.as_bytes()).deserialize::<T>() {
+        match row.ok().and_then(&into_record) {
+            Some(record) => import.records.push(record),
+            None => import.skipped += 1,
+        }
+    }
+    import
+}
+
+pub fn parse(text: &str, format: ImportFormat) -> Result<Import, String> {
+    let whole = |records: Vec<RunRecord>| Import { records, skipped: 0 };
+    match format {
+        ImportFormat::Monkeytype => Ok(read_rows(text, |row: MonkeytypeRow| Some(row.into_record()))),
+        ImportFormat::Typeracer => Ok(read_rows(text, TyperacerRow::into_record)),
+        ImportFormat::Export(ExportFormat::Csv) => export::from_csv(text).map(whole),
+        ImportFormat::Export(ExportFormat::Json) => export::from_json(text).map(whole),
+    }
+}
+
+/// reads file, format is guessed if it is not given
+pub fn read(path: &str, format: Option<ImportFormat>) -> Result<Import, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let format = format
+        .or_else(|| ImportFormat::detect(path, &text))
+        .ok_or(format!("can't tell format of {}", path))?;
+    parse(&text, format)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const MONKEYTYPE: &str = "\
+_id,isPb,wpm,acc,rawWpm,consistency,charStats,mode,mode2,quoteLength,restartCount,testDuration,afkDuration,incompleteTestSeconds,lazyMode,blindMode,bailedOut,tags,timestamp
+65a1,true,84.4,97.12,88.8,78.5,211;3;1;0,time,30,-1,0,30,0,0,false,false,false,,1705000000000
+65a2,false,70,95,74.2,70.1,\"350;10;2;4\",words,50,-1,2,42.5,0,0,false,false,false,tag1,1705100000000
+65a3,false,66.1,93,70,65,300;12;0;0,quote,1234,1,0,55.2,0,0,false,false,false,,1705200000000
+broken,false,fast,93,70,65,300;12;0;0,quote,1234,1,0,55.2,0,0,false,false,false,,1705200000000
+";
+
+    const TYPERACER: &str = "\
+Race #,WPM,Accuracy,Rank,# Racers,Text ID,Date/Time (UTC)
+1502,92,0.98,1,5,3810261,2019-11-02 18:04:11
+1501,88.5,96%,2,4,3550105,2019-11-02 17:58:40
+1500,80,97,3,5,3620327,yesterday
+";
+
+    #[test]
+    fn monkeytype_results() {
+        assert_eq!(ImportFormat::detect("results.csv", MONKEYTYPE), Some(ImportFormat::Monkeytype));
+        let import = parse(MONKEYTYPE, ImportFormat::Monkeytype).unwrap();
+        assert_eq!(import.skipped, 1);
+        let records = import.records;
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].finished_at, 1705000030);
+        assert_eq!(records[0].game_conf.limit, Limit::Time(Duration::from_secs(30)));
+        assert_eq!((records[0].wpm, records[0].accuracy), (84.4, 97.12));
+        assert_eq!((records[0].correct_strokes, records[0].wrong_strokes), (211, 4));
+        assert_eq!(records[1].game_conf.limit, Limit::WordCount(50));
+        assert_eq!(records[2].game_conf.limit, Limit::None);
+        assert!(records.iter().all(|r| r.origin == Origin::Monkeytype && r.counts_as_record()));
+    }
+
+    #[test]
+    fn typeracer_races() {
+        assert_eq!(ImportFormat::detect("races.csv", TYPERACER), Some(ImportFormat::Typeracer));
+        let import = parse(TYPERACER, ImportFormat::Typeracer).unwrap();
+        assert_eq!(import.skipped, 1);
+        assert_eq!(import.records[0].finished_at, 1572717851);
+        assert_eq!(import.records[0].accuracy, 98.0);
+        assert_eq!(import.records[1].accuracy, 96.0);
+        assert_eq!(import.records[1].origin, Origin::Typeracer);
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(parse_date("1970-01-02T00:01:05Z"), Some(86465));
+        assert_eq!(parse_date("2019-11-02"), Some(1572652800));
+        assert_eq!(parse_date("1572652800"), Some(1572652800));
+        assert_eq!(parse_date("2019-13-02"), None);
+        assert_eq!(parse_date("today"), None);
+    }
+}
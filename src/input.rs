@@ -1,45 +1,143 @@
-use crossterm::event::{read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::event::{poll, read, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use unicode_normalization::{char::is_combining_mark, UnicodeNormalization};
 
-use std::{io::Result, process::exit};
+use std::{collections::VecDeque, io::Result, time::Duration};
 
-fn try_read_key() -> Result<Option<InputSignal>> {
-    // Set terminal to raw mode
-    // let mut res = Ok(None);
-    // Read next event
-    if let Event::Key(key) = read()? {
-        if key.kind != KeyEventKind::Press {
-            return Ok(None);
-        }
-        match key.code {
-            KeyCode::Char(c) => {
-                if key.modifiers == KeyModifiers::CONTROL && c == 'c' {
-                    return Ok(Some(InputSignal::TerminateProgram));
-                }
-                if key.modifiers == KeyModifiers::CONTROL && (c == 'w' || c == 'h') {
-                    return Ok(Some(InputSignal::Key(KeyEvent {
-                        code: KeyCode::Backspace,
-                        modifiers: key.modifiers,
-                        kind: key.kind,
-                        state: key.state,
-                    })));
-                } else {
-                    return Ok(Some(InputSignal::Key(key)));
+use crate::layout::KeyboardLayout;
+
+/// dead keys sent by terminals that don't compose them, with the mark each of them puts on the letter,
+/// ASCII accents like `^` are left alone because they are ordinary keys on many layouts
+const DEAD_KEYS: [(char, char); 13] = [
+    ('´', '\u{301}'),
+    ('ˋ', '\u{300}'),
+    ('ˆ', '\u{302}'),
+    ('˜', '\u{303}'),
+    ('¯', '\u{304}'),
+    ('˘', '\u{306}'),
+    ('˙', '\u{307}'),
+    ('¨', '\u{308}'),
+    ('˚', '\u{30A}'),
+    ('˝', '\u{30B}'),
+    ('ˇ', '\u{30C}'),
+    ('¸', '\u{327}'),
+    ('˛', '\u{328}'),
+];
+
+/// combining mark of the dead key, `None` if char is not a dead key
+fn dead_key_mark(c: char) -> Option<char> {
+    DEAD_KEYS.iter().find(|(key, _)| *key == c).map(|(_, mark)| *mark)
+}
+
+/// letter with marks in composed form, letters without composed form stay split
+fn compose(letter: char, marks: &str) -> Vec<char> {
+    format!("{}{}", letter, marks).nfc().collect()
+}
+
+/// builds accented letters from dead keys and combining marks,
+/// so letter typed with dead key is passed to the game as one key press
+#[derive(Debug, Default)]
+pub struct Composer {
+    ///dead key waiting for the letter it is put on
+    dead_key: Option<char>,
+}
+impl Composer {
+    /// takes pressed key with combining marks that came right after it,
+    /// returns keys that should be handled, nothing while dead key waits for the letter
+    pub fn feed(&mut self, key: KeyEvent, marks: &str) -> Vec<KeyEvent> {
+        let with_char = |c: char| KeyEvent::new_with_kind(KeyCode::Char(c), key.modifiers, key.kind);
+        let c = match key.code {
+            KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => c,
+            //other keys cancel dead key
+            _ => {
+                self.dead_key = None;
+                return vec![key];
+            }
+        };
+        match self.dead_key.take() {
+            //space or second dead key types the accent itself
+            Some(dead) if c == ' ' || dead_key_mark(c).is_some() => vec![with_char(dead)],
+            Some(dead) => {
+                let mark = dead_key_mark(dead).unwrap_or_default();
+                match compose(c, &format!("{}{}", mark, marks)).as_slice() {
+                    [composed] => vec![with_char(*composed)],
+                    //letter can't take the accent, both are typed like keyboard does
+                    _ => std::iter::once(dead).chain(compose(c, marks)).map(with_char).collect(),
                 }
             }
-            _ => {
-                return Ok(Some(InputSignal::Key(key)));
+            None if dead_key_mark(c).is_some() && marks.is_empty() => {
+                self.dead_key = Some(c);
+                Vec::new()
             }
+            None => compose(c, marks).into_iter().map(with_char).collect(),
         }
     }
-    Ok(None)
 }
-pub fn get_input_process_input() -> Option<InputSignal> {
-    match try_read_key() {
-        Ok(key) => key,
-        Err(emsg) => {
-            eprintln!("problem occurred during input processing\n{}", emsg);
-            exit(0);
+
+/// turns char typed on `from` layout into char of the same key on `to` layout,
+/// so layout can be learned without changing system settings,
+/// chars `from` layout doesn't have and keys with Ctrl or Alt stay as they are
+pub fn emulate_layout(key: KeyEvent, from: &KeyboardLayout, to: &KeyboardLayout) -> KeyEvent {
+    match key.code {
+        KeyCode::Char(c) if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            let c = from.key(c).and_then(|k| to.char_at(k)).unwrap_or(c);
+            KeyEvent::new_with_kind(KeyCode::Char(c), key.modifiers, key.kind)
         }
+        _ => key,
+    }
+}
+
+/// reads terminal events, keeps composition state between reads
+#[derive(Debug, Default)]
+pub struct InputReader {
+    composer: Composer,
+    ///event read while looking for combining marks after a letter
+    ahead: Option<Event>,
+    ///signals that are ready but not handed out yet
+    ready: VecDeque<InputSignal>,
+}
+impl InputReader {
+    /// waits for input for given time, input errors are passed as signals so the program can stop properly
+    pub fn next(&mut self, timeout: Duration) -> Option<InputSignal> {
+        if self.ready.is_empty() {
+            if let Err(emsg) = self.read(timeout) {
+                return Some(InputSignal::Error(emsg.to_string()));
+            }
+        }
+        self.ready.pop_front()
+    }
+
+    fn read(&mut self, timeout: Duration) -> Result<()> {
+        let event = match self.ahead.take() {
+            Some(event) => event,
+            None if poll(timeout)? => read()?,
+            None => return Ok(()),
+        };
+        match event {
+            //repeats of held down keys are passed too, game needs to know about them
+            Event::Key(key) if key.kind != KeyEventKind::Release => {
+                //terminal may send accented letter as letter followed by combining marks
+                let mut marks = String::new();
+                if let KeyCode::Char(_) = key.code {
+                    while self.ahead.is_none() && poll(Duration::ZERO)? {
+                        match read()? {
+                            Event::Key(KeyEvent {
+                                code: KeyCode::Char(m),
+                                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                                ..
+                            }) if is_combining_mark(m) => marks.push(m),
+                            other => self.ahead = Some(other),
+                        }
+                    }
+                }
+                //key meaning is decided by key map
+                self.ready.extend(self.composer.feed(key, &marks).into_iter().map(InputSignal::Key));
+            }
+            Event::Resize(_, _) => self.ready.push_back(InputSignal::Resize),
+            Event::FocusLost => self.ready.push_back(InputSignal::FocusLost),
+            Event::Paste(text) => self.ready.push_back(InputSignal::Paste(text)),
+            _ => (),
+        }
+        Ok(())
     }
 }
 /* pub fn get_input_message() -> Option<Message> {
@@ -59,5 +157,87 @@ pub fn get_input_process_input() -> Option<InputSignal> {
 #[derive(Debug)]
 pub enum InputSignal {
     Key(KeyEvent),
-    TerminateProgram,
+    ///terminal size changed, screen should be drawn again
+    Resize,
+    ///terminal window is not active anymore
+    FocusLost,
+    ///text pasted with bracketed paste
+    Paste(String),
+    ///reading input failed
+    Error(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{Game, GameConf, Limit};
+    use crate::layout;
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    fn chars(keys: Vec<KeyEvent>) -> String {
+        keys.iter()
+            .filter_map(|k| match k.code {
+                KeyCode::Char(c) => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn dead_keys_are_composed() {
+        let mut composer = Composer::default();
+        assert!(composer.feed(key('´'), "").is_empty());
+        assert_eq!(chars(composer.feed(key('e'), "")), "é");
+        assert!(composer.feed(key('¨'), "").is_empty());
+        assert_eq!(chars(composer.feed(key('u'), "")), "ü");
+        //accent alone and letter that can't take it
+        composer.feed(key('´'), "");
+        assert_eq!(chars(composer.feed(key(' '), "")), "´");
+        composer.feed(key('ˇ'), "");
+        assert_eq!(chars(composer.feed(key('q'), "")), "ˇq");
+        //other key drops waiting dead key
+        let backspace = KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE);
+        composer.feed(key('´'), "");
+        assert_eq!(composer.feed(backspace, ""), vec![backspace]);
+        assert_eq!(chars(composer.feed(key('e'), "")), "e");
+        //letter sent with combining mark
+        assert_eq!(chars(composer.feed(key('e'), "\u{301}")), "é");
+        assert_eq!(chars(composer.feed(key('^'), "")), "^");
+    }
+
+    #[test]
+    fn composed_letter_is_one_keystroke() {
+        let conf = GameConf {
+            limit: Limit::None,
+            ..GameConf::new()
+        };
+        let mut game = Game::from_text(conf, "café");
+        let mut composer = Composer::default();
+        for c in "caf´e".chars() {
+            for key in composer.feed(key(c), "") {
+                if let KeyCode::Char(c) = key.code {
+                    game.char_key_pressed(c);
+                }
+            }
+        }
+        assert!(game.is_complete());
+        assert_eq!(game.statistics.keystrokes.len(), 4);
+        assert_eq!(game.statistics.wrong_strokes, 0);
+    }
+
+    #[test]
+    fn keys_are_remapped_to_emulated_layout() {
+        let layouts = layout::bundled();
+        let qwerty = layout::find(&layouts, "qwerty").unwrap();
+        let colemak = layout::find(&layouts, "colemak").unwrap();
+        let typed: Vec<KeyEvent> = "asdf JKL;".chars().map(|c| emulate_layout(key(c), qwerty, colemak)).collect();
+        assert_eq!(chars(typed), "arst NEIo");
+        //chars qwerty doesn't have and shortcuts are kept
+        assert_eq!(chars(vec![emulate_layout(key('ł'), qwerty, colemak)]), "ł");
+        let ctrl_d = KeyEvent::new(KeyCode::Char('d'), KeyModifiers::CONTROL);
+        assert_eq!(emulate_layout(ctrl_d, qwerty, colemak), ctrl_d);
+    }
 }
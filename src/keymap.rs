@@ -0,0 +1,291 @@
+use std::collections::BTreeMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+/// what user wants to do, components react to actions instead of keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    DeleteChar,
+    DeleteWord,
+    ///start game with the same text again
+    Restart,
+    ///start game with new text and the same configuration
+    NextText,
+    ///open game configuration window
+    GameConfig,
+    Pause,
+    ///save results of finished run to file
+    Export,
+    ///save selected item under a name, like game configuration preset
+    Save,
+    ///create new item, like profile
+    Add,
+    Rename,
+    ///delete selected item
+    Remove,
+    Quit,
+}
+
+/// what active window expects from keyboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeyContext {
+    ///moving around windows, letters can be bound to actions
+    Navigation,
+    ///typing text, letters are always typed
+    Typing,
+}
+
+/// key that quits in every context whatever key map says, so no key map can lock the player in
+pub const QUIT_KEY: &str = "Ctrl+c";
+
+/// table of key bindings, keys are written like "Ctrl+w", "Esc" or "j"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct KeyMap {
+    pub navigation: BTreeMap<String, Action>,
+    pub typing: BTreeMap<String, Action>,
+}
+impl Default for KeyMap {
+    fn default() -> Self {
+        KeyMap::preset(KeymapPreset::Default)
+    }
+}
+impl KeyMap {
+    /// builds key map from preset
+    pub fn preset(preset: KeymapPreset) -> KeyMap {
+        let table = |bindings: &[(&str, Action)]| -> BTreeMap<String, Action> {
+            bindings.iter().map(|(k, a)| (k.to_string(), *a)).collect()
+        };
+        //keys that work the same in every context
+        let common = [
+            ("Up", Action::Up),
+            ("Down", Action::Down),
+            ("Left", Action::Left),
+            ("Right", Action::Right),
+            ("Enter", Action::Confirm),
+            ("Esc", Action::Back),
+            ("Backspace", Action::DeleteChar),
+            (QUIT_KEY, Action::Quit),
+        ];
+        let mut navigation = table(&common);
+        navigation.extend(table(&[
+            ("Space", Action::Confirm),
+            ("r", Action::Restart),
+            ("n", Action::NextText),
+            ("c", Action::GameConfig),
+            ("e", Action::Export),
+            ("s", Action::Save),
+            ("a", Action::Add),
+            ("F2", Action::Rename),
+            ("Delete", Action::Remove),
+        ]));
+        let mut typing = table(&common);
+        typing.extend(table(&[
+            ("Ctrl+Backspace", Action::DeleteWord),
+            ("Ctrl+w", Action::DeleteWord),
+            ("Ctrl+h", Action::DeleteWord), //some terminals send Ctrl+h for Ctrl+Backspace
+            ("Tab", Action::Restart),
+            ("Ctrl+n", Action::NextText),
+            ("Ctrl+g", Action::GameConfig),
+            ("Ctrl+p", Action::Pause),
+        ]));
+        if preset == KeymapPreset::Vim {
+            navigation.extend(table(&[
+                ("k", Action::Up),
+                ("j", Action::Down),
+                ("h", Action::Left),
+                ("l", Action::Right),
+                ("q", Action::Back),
+            ]));
+            typing.extend(table(&[
+                ("Ctrl+k", Action::Up),
+                ("Ctrl+j", Action::Down),
+            ]));
+        }
+        KeyMap { navigation, typing }
+    }
+
+    /// returns preset this key map is built from, `None` if it is changed by user
+    pub fn get_preset(&self) -> Option<KeymapPreset> {
+        [KeymapPreset::Default, KeymapPreset::Vim]
+            .into_iter()
+            .find(|p| KeyMap::preset(*p) == *self)
+    }
+
+    /// binds actions that have no key to their keys from default preset if the keys are free,
+    /// key maps saved before an action was added don't lose it
+    pub fn bind_missing(&mut self) {
+        let default = KeyMap::default();
+        for context in [KeyContext::Navigation, KeyContext::Typing] {
+            for (key, action) in default.table(context) {
+                if self.keys(context, *action).is_empty() && !self.table(context).contains_key(key) {
+                    self.table_mut(context).insert(key.clone(), *action);
+                }
+            }
+        }
+    }
+
+    /// names of keys bound to the action, written the way `key_name` writes them
+    pub fn keys(&self, context: KeyContext, action: Action) -> Vec<&str> {
+        self.table(context)
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    /// help line like "Tab = restart | Ctrl+n = new text" for actions with what they do,
+    /// actions without keys are left out
+    pub fn hints(&self, context: KeyContext, actions: &[(&[Action], &str)]) -> String {
+        actions
+            .iter()
+            .filter_map(|(actions, what)| {
+                let keys: Vec<&str> = actions.iter().flat_map(|a| self.keys(context, *a)).collect();
+                if keys.is_empty() {
+                    None
+                } else {
+                    Some(format!("{} = {}", keys.join("/"), what))
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" | ")
+    }
+
+    fn table(&self, context: KeyContext) -> &BTreeMap<String, Action> {
+        match context {
+            KeyContext::Navigation => &self.navigation,
+            KeyContext::Typing => &self.typing,
+        }
+    }
+
+    fn table_mut(&mut self, context: KeyContext) -> &mut BTreeMap<String, Action> {
+        match context {
+            KeyContext::Navigation => &mut self.navigation,
+            KeyContext::Typing => &mut self.typing,
+        }
+    }
+
+    /// finds action bound to the key
+    pub fn resolve(&self, context: KeyContext, key: &KeyEvent) -> Option<Action> {
+        let name = key_name(key)?;
+        if name == QUIT_KEY {
+            return Some(Action::Quit);
+        }
+        self.table(context).get(&name).copied()
+    }
+}
+
+/// built in key maps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeymapPreset {
+    Default,
+    Vim,
+}
+
+/// returns name of the key as it is written in key map, `None` for keys that can't be bound
+pub fn key_name(key: &KeyEvent) -> Option<String> {
+    let code = match key.code {
+        KeyCode::Char(' ') => "Space".to_string(),
+        KeyCode::Char(c) => c.to_string(),
+        KeyCode::F(n) => format!("F{}", n),
+        KeyCode::Backspace => "Backspace".to_string(),
+        KeyCode::Enter => "Enter".to_string(),
+        KeyCode::Left => "Left".to_string(),
+        KeyCode::Right => "Right".to_string(),
+        KeyCode::Up => "Up".to_string(),
+        KeyCode::Down => "Down".to_string(),
+        KeyCode::Home => "Home".to_string(),
+        KeyCode::End => "End".to_string(),
+        KeyCode::PageUp => "PageUp".to_string(),
+        KeyCode::PageDown => "PageDown".to_string(),
+        KeyCode::Tab => "Tab".to_string(),
+        KeyCode::BackTab => "BackTab".to_string(),
+        KeyCode::Delete => "Delete".to_string(),
+        KeyCode::Insert => "Insert".to_string(),
+        KeyCode::Esc => "Esc".to_string(),
+        _ => return None,
+    };
+    let mut name = String::new();
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        name.push_str("Ctrl+");
+    }
+    if key.modifiers.contains(KeyModifiers::ALT) {
+        name.push_str("Alt+");
+    }
+    //shift is already a part of character
+    if key.modifiers.contains(KeyModifiers::SHIFT) && !matches!(key.code, KeyCode::Char(_)) {
+        name.push_str("Shift+");
+    }
+    name.push_str(&code);
+    Some(name)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn key_names() {
+        let key = |code, modifiers| KeyEvent::new(code, modifiers);
+        assert_eq!(key_name(&key(KeyCode::Char('w'), KeyModifiers::CONTROL)).unwrap(), "Ctrl+w");
+        assert_eq!(key_name(&key(KeyCode::Char('J'), KeyModifiers::SHIFT)).unwrap(), "J");
+        assert_eq!(key_name(&key(KeyCode::Char(' '), KeyModifiers::NONE)).unwrap(), "Space");
+        assert_eq!(key_name(&key(KeyCode::Tab, KeyModifiers::SHIFT)).unwrap(), "Shift+Tab");
+    }
+
+    #[test]
+    fn letters_are_typed_in_typing_context() {
+        let keymap = KeyMap::preset(KeymapPreset::Vim);
+        let j = KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE);
+        assert_eq!(keymap.resolve(KeyContext::Navigation, &j), Some(Action::Down));
+        assert_eq!(keymap.resolve(KeyContext::Typing, &j), None);
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.resolve(KeyContext::Typing, &ctrl_w), Some(Action::DeleteWord));
+    }
+
+    #[test]
+    fn hints_follow_bindings() {
+        let actions: &[(&[Action], &str)] = &[(&[Action::Back], "go to menu"), (&[Action::Pause], "pause")];
+        let keymap = KeyMap::preset(KeymapPreset::Default);
+        assert_eq!(keymap.hints(KeyContext::Navigation, actions), "Esc = go to menu");
+        assert_eq!(keymap.hints(KeyContext::Typing, actions), "Esc = go to menu | Ctrl+p = pause");
+        let vim = KeyMap::preset(KeymapPreset::Vim);
+        assert_eq!(vim.hints(KeyContext::Navigation, actions), "Esc/q = go to menu");
+    }
+
+    #[test]
+    fn missing_actions_are_bound() {
+        let mut keymap = KeyMap::preset(KeymapPreset::Vim);
+        keymap.navigation.retain(|_, action| *action != Action::Remove && *action != Action::Rename);
+        keymap.navigation.insert("F2".to_string(), Action::Export);
+        keymap.bind_missing();
+        assert_eq!(keymap.keys(KeyContext::Navigation, Action::Remove), vec!["Delete"]);
+        //taken key is not bound again
+        assert!(keymap.keys(KeyContext::Navigation, Action::Rename).is_empty());
+        assert_eq!(keymap.keys(KeyContext::Navigation, Action::Down), vec!["Down", "j"]);
+    }
+
+    #[test]
+    fn quit_key_can_not_be_rebound() {
+        let mut keymap = KeyMap::default();
+        keymap.navigation.retain(|_, action| *action != Action::Quit);
+        keymap.typing.insert(QUIT_KEY.to_string(), Action::Pause);
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(keymap.resolve(KeyContext::Navigation, &ctrl_c), Some(Action::Quit));
+        assert_eq!(keymap.resolve(KeyContext::Typing, &ctrl_c), Some(Action::Quit));
+    }
+
+    #[test]
+    fn keymap_round_trip() {
+        let keymap = KeyMap::preset(KeymapPreset::Vim);
+        let read: KeyMap = toml::from_str(&toml::to_string(&keymap).unwrap()).unwrap();
+        assert_eq!(read.get_preset(), Some(KeymapPreset::Vim));
+    }
+}
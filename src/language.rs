@@ -0,0 +1,265 @@
+//! Language packs used to generate text
+//!
+//! Every pack is a folder with:
+//! - `corpus.txt`: text the Markov chain is built from
+//! - `words.txt` (optional): common words, one per line, used to start text and after dead ends of the chain
+//! - `pack.toml` (optional): `name = "Shown name"`, folder name is shown if it is missing
+//!
+//! Packs for English, German, French, Spanish, Russian and Polish are built into the program.
+//! Users drop their own packs into languages folder from settings, folder name is the id of the pack.
+//! User pack with id of built-in pack replaces it.
+//! Text older versions generated text from is imported once as user pack `custom`.
+
+use std::{collections::HashMap, fs, path::Path};
+
+use serde::Deserialize;
+use typing_engine::MarkovChain;
+
+/// language of generated text when configuration doesn't say otherwise
+pub const DEFAULT_LANGUAGE: &str = "en";
+const CORPUS_FILE: &str = "corpus.txt";
+const WORDS_FILE: &str = "words.txt";
+const PACK_FILE: &str = "pack.toml";
+/// id of pack made from text for generation of older versions
+pub const IMPORTED_LANGUAGE: &str = "custom";
+/// text for generation of older versions when settings didn't name another file
+pub const LEGACY_TEXT: &str = "text_gen.txt";
+
+/// id, pack.toml, corpus and word list of every built-in pack
+const BUNDLED: [(&str, &str, &str, &str); 6] = [
+    (
+        "en",
+        include_str!("languages/en/pack.toml"),
+        include_str!("languages/en/corpus.txt"),
+        include_str!("languages/en/words.txt"),
+    ),
+    (
+        "de",
+        include_str!("languages/de/pack.toml"),
+        include_str!("languages/de/corpus.txt"),
+        include_str!("languages/de/words.txt"),
+    ),
+    (
+        "fr",
+        include_str!("languages/fr/pack.toml"),
+        include_str!("languages/fr/corpus.txt"),
+        include_str!("languages/fr/words.txt"),
+    ),
+    (
+        "es",
+        include_str!("languages/es/pack.toml"),
+        include_str!("languages/es/corpus.txt"),
+        include_str!("languages/es/words.txt"),
+    ),
+    (
+        "ru",
+        include_str!("languages/ru/pack.toml"),
+        include_str!("languages/ru/corpus.txt"),
+        include_str!("languages/ru/words.txt"),
+    ),
+    (
+        "pl",
+        include_str!("languages/pl/pack.toml"),
+        include_str!("languages/pl/corpus.txt"),
+        include_str!("languages/pl/words.txt"),
+    ),
+];
+
+/// text and words of one language
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguagePack {
+    ///name of pack folder, kept in game configuration
+    pub id: String,
+    ///name shown to the user
+    pub name: String,
+    pub corpus: String,
+    pub words: Vec<String>,
+}
+impl LanguagePack {
+    fn new(id: &str, pack_toml: &str, corpus: &str, words: &str) -> LanguagePack {
+        let conf: PackConf = toml::from_str(pack_toml).unwrap_or_default();
+        LanguagePack {
+            id: id.to_string(),
+            name: conf.name.unwrap_or(id.to_string()),
+            corpus: corpus.to_string(),
+            words: words.lines().map(|w| w.trim().to_string()).filter(|w| !w.is_empty()).collect(),
+        }
+    }
+}
+
+/// contents of pack.toml
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct PackConf {
+    name: Option<String>,
+}
+
+/// packs built into the program
+pub fn bundled() -> Vec<LanguagePack> {
+    BUNDLED
+        .iter()
+        .map(|(id, pack_toml, corpus, words)| LanguagePack::new(id, pack_toml, corpus, words))
+        .collect()
+}
+
+/// reads pack from folder, only corpus is required
+pub fn read_pack(dir: &Path) -> Result<LanguagePack, String> {
+    let id = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or(format!("{} is not a language pack folder", dir.display()))?;
+    let corpus = fs::read_to_string(dir.join(CORPUS_FILE)).map_err(|e| format!("{}: {}", dir.join(CORPUS_FILE).display(), e))?;
+    let words = fs::read_to_string(dir.join(WORDS_FILE)).unwrap_or_default();
+    let pack_toml = fs::read_to_string(dir.join(PACK_FILE)).unwrap_or_default();
+    Ok(LanguagePack::new(id, &pack_toml, &corpus, &words))
+}
+
+/// packs dropped by user into the folder, folders without corpus are ignored
+pub fn user_packs(dir: &str) -> Vec<LanguagePack> {
+    let mut packs: Vec<LanguagePack> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| read_pack(&entry.path()).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    packs.sort_by(|a, b| a.id.cmp(&b.id));
+    packs
+}
+
+/// built-in packs followed by user packs, user pack replaces built-in pack with the same id
+pub fn all(dir: &str) -> Vec<LanguagePack> {
+    let mut packs = bundled();
+    for pack in user_packs(dir) {
+        match packs.iter().position(|p| p.id == pack.id) {
+            Some(i) => packs[i] = pack,
+            None => packs.push(pack),
+        }
+    }
+    packs
+}
+
+/// copies text for generation of older versions into user pack, returns what was done,
+/// `None` if there is no such text or it was imported before
+pub fn import_legacy_text(path: &str, dir: &str) -> Option<String> {
+    let pack_dir = Path::new(dir).join(IMPORTED_LANGUAGE);
+    if pack_dir.exists() {
+        return None;
+    }
+    let corpus = fs::read_to_string(path).ok()?;
+    let result = fs::create_dir_all(&pack_dir)
+        .and_then(|_| fs::write(pack_dir.join(CORPUS_FILE), corpus))
+        .and_then(|_| fs::write(pack_dir.join(PACK_FILE), "name = \"Custom text\"\n"));
+    Some(match result {
+        Ok(()) => format!("{} was imported as language Custom text", path),
+        Err(e) => format!("can't import {} as language: {}", path, e),
+    })
+}
+
+/// available language packs, chain of every pack is built the first time it is used
+#[derive(Debug, Default)]
+pub struct Languages {
+    pub packs: Vec<LanguagePack>,
+    chains: HashMap<String, MarkovChain>,
+}
+impl Languages {
+    /// reads user packs from the folder
+    pub fn new(dir: &str) -> Languages {
+        Languages {
+            packs: all(dir),
+            chains: HashMap::new(),
+        }
+    }
+
+    /// pack with given id, default language if there is no such pack
+    pub fn pack(&self, id: &str) -> Option<&LanguagePack> {
+        self.packs
+            .iter()
+            .find(|p| p.id == id)
+            .or(self.packs.iter().find(|p| p.id == DEFAULT_LANGUAGE))
+    }
+
+    /// ids and names of all packs
+    pub fn names(&self) -> Vec<(String, String)> {
+        self.packs.iter().map(|p| (p.id.clone(), p.name.clone())).collect()
+    }
+
+    /// chain for generating text in the language
+    pub fn chain(&mut self, id: &str) -> &MarkovChain {
+        let pack = self.pack(id).cloned();
+        let key = pack.as_ref().map(|p| p.id.clone()).unwrap_or_default();
+        self.chains.entry(key).or_insert_with(|| match pack {
+            Some(pack) => MarkovChain::new(&pack.corpus, &pack.words),
+            None => MarkovChain::default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn bundled_packs() {
+        let packs = bundled();
+        let ids: Vec<&str> = packs.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["en", "de", "fr", "es", "ru", "pl"]);
+        for pack in &packs {
+            assert_ne!(pack.name, pack.id);
+            assert!(pack.corpus.split_whitespace().count() > 200);
+            assert!(pack.words.len() > 100);
+        }
+        let mut languages = Languages {
+            packs,
+            ..Languages::default()
+        };
+        let text = languages.chain("ru").generate(20);
+        assert!(text.chars().any(|c| ('а'..='я').contains(&c)));
+        //unknown language falls back to English
+        assert_eq!(languages.pack("xx").unwrap().name, "English");
+    }
+
+    #[test]
+    fn user_packs_are_read_from_folder() {
+        let dir = env::temp_dir().join(format!("typing_game_languages_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("eo")).unwrap();
+        fs::write(dir.join("eo").join(CORPUS_FILE), "saluton mondo").unwrap();
+        fs::write(dir.join("eo").join(PACK_FILE), "name = \"Esperanto\"").unwrap();
+        fs::create_dir_all(dir.join("en")).unwrap();
+        fs::write(dir.join("en").join(CORPUS_FILE), "aa aa aa").unwrap();
+        fs::create_dir_all(dir.join("empty")).unwrap();
+
+        let mut languages = Languages::new(dir.to_str().unwrap());
+        assert_eq!(languages.packs.len(), 7);
+        assert_eq!(languages.pack("eo").unwrap().name, "Esperanto");
+        //user pack replaces built-in one
+        assert_eq!(languages.pack("en").unwrap().name, "en");
+        assert_eq!(languages.chain("en").generate(3), "aa aa aa ");
+        assert!(languages.pack("empty").unwrap().id == DEFAULT_LANGUAGE);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn legacy_text_is_imported_once() {
+        let dir = env::temp_dir().join(format!("typing_game_legacy_text_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let text = dir.join(LEGACY_TEXT);
+        let languages = dir.join("languages");
+        let (text, languages) = (text.to_str().unwrap(), languages.to_str().unwrap());
+        assert_eq!(import_legacy_text(text, languages), None);
+
+        fs::write(text, "bb bb bb").unwrap();
+        assert!(import_legacy_text(text, languages).unwrap().contains("imported"));
+        let mut imported = Languages::new(languages);
+        assert_eq!(imported.pack(IMPORTED_LANGUAGE).unwrap().name, "Custom text");
+        assert_eq!(imported.chain(IMPORTED_LANGUAGE).generate(3), "bb bb bb ");
+        //changes of the pack are not overwritten
+        assert_eq!(import_legacy_text(text, languages), None);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,309 @@
+//! Keyboard layouts: which key and finger types every char
+//!
+//! Layout is a TOML file:
+//!
+//! ```toml
+//! name = "QWERTY"
+//! rows = ["`1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", " zxcvbnm,./"]
+//! shift_rows = ["~!@#$%^&*()_+", "QWERTYUIOP{}|", "ASDFGHJKL:\"", " ZXCVBNM<>?"]
+//! ```
+//!
+//! Rows go from number row to bottom row of a row-staggered keyboard, every char is one key from the left.
+//! Top row has up to 13 keys (the last one is `\` of ANSI keyboards), home row up to 12
+//! (the last one is `#` of ISO keyboards), bottom row up to 11 (the first one is `<` of ISO keyboards).
+//! Space marks a missing key. `shift_rows` are optional, upper case of `rows` is used without them.
+//!
+//! QWERTY, Dvorak, Colemak, Colemak-DH, AZERTY and QWERTZ are built into the program,
+//! user layouts are read from layouts folder from settings, file name is the id of the layout.
+
+use std::{fs, path::Path};
+
+use serde::Deserialize;
+
+/// layout used when settings don't name a known one
+pub const DEFAULT_LAYOUT: &str = "qwerty";
+/// most keys in every row, from number row to bottom row
+const ROW_LENGTHS: [usize; 4] = [13, 13, 12, 11];
+/// row of space bar
+pub const SPACE_ROW: usize = 4;
+
+/// id and file contents of every built-in layout
+const BUNDLED: [(&str, &str); 6] = [
+    ("qwerty", include_str!("layouts/qwerty.toml")),
+    ("dvorak", include_str!("layouts/dvorak.toml")),
+    ("colemak", include_str!("layouts/colemak.toml")),
+    ("colemak_dh", include_str!("layouts/colemak_dh.toml")),
+    ("azerty", include_str!("layouts/azerty.toml")),
+    ("qwertz", include_str!("layouts/qwertz.toml")),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+/// finger that presses the key in touch typing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Finger {
+    LeftPinky,
+    LeftRing,
+    LeftMiddle,
+    LeftIndex,
+    ///both thumbs, they press only space
+    Thumb,
+    RightIndex,
+    RightMiddle,
+    RightRing,
+    RightPinky,
+}
+impl Finger {
+    pub const ALL: [Finger; 9] = [
+        Finger::LeftPinky,
+        Finger::LeftRing,
+        Finger::LeftMiddle,
+        Finger::LeftIndex,
+        Finger::Thumb,
+        Finger::RightIndex,
+        Finger::RightMiddle,
+        Finger::RightRing,
+        Finger::RightPinky,
+    ];
+
+    /// hand of the finger, `None` for thumbs
+    pub fn hand(&self) -> Option<Hand> {
+        match self {
+            Finger::LeftPinky | Finger::LeftRing | Finger::LeftMiddle | Finger::LeftIndex => Some(Hand::Left),
+            Finger::Thumb => None,
+            Finger::RightIndex | Finger::RightMiddle | Finger::RightRing | Finger::RightPinky => Some(Hand::Right),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Finger::LeftPinky => "left pinky",
+            Finger::LeftRing => "left ring",
+            Finger::LeftMiddle => "left middle",
+            Finger::LeftIndex => "left index",
+            Finger::Thumb => "thumb",
+            Finger::RightIndex => "right index",
+            Finger::RightMiddle => "right middle",
+            Finger::RightRing => "right ring",
+            Finger::RightPinky => "right pinky",
+        }
+    }
+}
+
+/// place of key on keyboard, row 0 is number row and row 4 is space bar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyPos {
+    pub row: usize,
+    pub col: usize,
+}
+impl KeyPos {
+    /// finger that presses key at this place
+    pub fn finger(&self) -> Finger {
+        use Finger::*;
+        let fingers: &[Finger] = match self.row {
+            0 => &[LeftPinky, LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex, RightMiddle, RightRing],
+            1 | 2 => &[LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex, RightMiddle, RightRing],
+            3 => &[LeftPinky, LeftPinky, LeftRing, LeftMiddle, LeftIndex, LeftIndex, RightIndex, RightIndex, RightMiddle, RightRing],
+            _ => return Thumb,
+        };
+        //keys right of ring finger are pressed by pinky
+        fingers.get(self.col).copied().unwrap_or(RightPinky)
+    }
+}
+
+/// key that types a char
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Key {
+    pub pos: KeyPos,
+    ///shift has to be held
+    pub shift: bool,
+}
+impl Key {
+    pub fn finger(&self) -> Finger {
+        self.pos.finger()
+    }
+}
+
+/// chars of every key of a keyboard
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct KeyboardLayout {
+    ///file name of the layout, kept in settings
+    #[serde(skip)]
+    pub id: String,
+    ///name shown to the user
+    pub name: String,
+    pub rows: Vec<String>,
+    #[serde(default)]
+    pub shift_rows: Vec<String>,
+}
+impl KeyboardLayout {
+    /// reads layout from TOML text and checks that rows fit the keyboard
+    pub fn parse(id: &str, text: &str) -> Result<KeyboardLayout, String> {
+        let mut layout: KeyboardLayout = toml::from_str(text).map_err(|e| format!("layout {}: {}", id, e))?;
+        layout.id = id.to_string();
+        if layout.rows.len() != ROW_LENGTHS.len() {
+            return Err(format!("layout {} should have {} rows", id, ROW_LENGTHS.len()));
+        }
+        if layout.shift_rows.is_empty() {
+            layout.shift_rows = layout.rows.iter().map(|row| row.chars().map(upper_case).collect()).collect();
+        }
+        if layout.shift_rows.len() != ROW_LENGTHS.len() {
+            return Err(format!("layout {} should have {} shift rows", id, ROW_LENGTHS.len()));
+        }
+        for (i, max) in ROW_LENGTHS.iter().enumerate() {
+            if layout.rows[i].chars().count() > *max || layout.shift_rows[i].chars().count() > *max {
+                return Err(format!("row {} of layout {} is longer than {} keys", i + 1, id, max));
+            }
+        }
+        Ok(layout)
+    }
+
+    /// key that types the char, the first one if there are many
+    pub fn key(&self, c: char) -> Option<Key> {
+        if c == ' ' {
+            return Some(Key {
+                pos: KeyPos { row: SPACE_ROW, col: 0 },
+                shift: false,
+            });
+        }
+        [(&self.rows, false), (&self.shift_rows, true)].iter().find_map(|(rows, shift)| {
+            rows.iter().enumerate().find_map(|(row, keys)| {
+                keys.chars().position(|k| k == c).map(|col| Key {
+                    pos: KeyPos { row, col },
+                    shift: *shift,
+                })
+            })
+        })
+    }
+
+    /// char typed by the key, `None` if there is no such key
+    pub fn char_at(&self, key: Key) -> Option<char> {
+        if key.pos.row == SPACE_ROW {
+            return Some(' ');
+        }
+        let rows = if key.shift { &self.shift_rows } else { &self.rows };
+        rows.get(key.pos.row)?.chars().nth(key.pos.col).filter(|c| *c != ' ')
+    }
+}
+
+/// upper case of the letter if it is one char, like it is on keyboard
+fn upper_case(c: char) -> char {
+    let mut upper = c.to_uppercase();
+    match (upper.next(), upper.next()) {
+        (Some(u), None) => u,
+        _ => c,
+    }
+}
+
+/// layouts built into the program
+pub fn bundled() -> Vec<KeyboardLayout> {
+    BUNDLED
+        .iter()
+        .filter_map(|(id, text)| KeyboardLayout::parse(id, text).ok())
+        .collect()
+}
+
+/// layouts from `.toml` files in the folder, broken files are skipped
+pub fn user_layouts(dir: &str) -> Vec<KeyboardLayout> {
+    let mut layouts: Vec<KeyboardLayout> = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+                .filter_map(|path| read_layout(&path).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    layouts.sort_by(|a, b| a.id.cmp(&b.id));
+    layouts
+}
+
+pub fn read_layout(path: &Path) -> Result<KeyboardLayout, String> {
+    let id = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .ok_or(format!("{} is not a layout file", path.display()))?;
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    KeyboardLayout::parse(id, &text)
+}
+
+/// built-in layouts followed by user layouts, user layout replaces built-in layout with the same id
+pub fn all(dir: &str) -> Vec<KeyboardLayout> {
+    let mut layouts = bundled();
+    for layout in user_layouts(dir) {
+        match layouts.iter().position(|l| l.id == layout.id) {
+            Some(i) => layouts[i] = layout,
+            None => layouts.push(layout),
+        }
+    }
+    layouts
+}
+
+/// layout with the id, `None` if there is no such layout
+pub fn find<'a>(layouts: &'a [KeyboardLayout], id: &str) -> Option<&'a KeyboardLayout> {
+    layouts.iter().find(|l| l.id == id)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    /// finger that types the char on the layout
+    fn finger(layout: &KeyboardLayout, c: char) -> Option<Finger> {
+        layout.key(c).map(|key| key.finger())
+    }
+
+    #[test]
+    fn bundled_layouts() {
+        let layouts = bundled();
+        let ids: Vec<&str> = layouts.iter().map(|l| l.id.as_str()).collect();
+        assert_eq!(ids, vec!["qwerty", "dvorak", "colemak", "colemak_dh", "azerty", "qwertz"]);
+        let layout = |id: &str| find(&layouts, id).unwrap();
+        //home row keys are under the same fingers in every layout
+        assert_eq!(finger(layout("qwerty"), 'f'), Some(Finger::LeftIndex));
+        assert_eq!(finger(layout("dvorak"), 'u'), Some(Finger::LeftIndex));
+        assert_eq!(finger(layout("colemak"), 't'), Some(Finger::LeftIndex));
+        assert_eq!(finger(layout("colemak_dh"), 'd'), Some(Finger::LeftIndex));
+        assert_eq!(finger(layout("qwerty"), 'p'), Some(Finger::RightPinky));
+        assert_eq!(finger(layout("qwerty"), ' '), Some(Finger::Thumb));
+        assert_eq!(finger(layout("azerty"), 'a'), Some(Finger::LeftPinky));
+        assert_eq!(finger(layout("azerty"), 'é'), Some(Finger::LeftRing));
+        assert_eq!(finger(layout("qwertz"), 'ö'), Some(Finger::RightPinky));
+
+        let qwerty = layout("qwerty");
+        let shifted = qwerty.key('A').unwrap();
+        assert!(shifted.shift);
+        assert_eq!(shifted.pos, qwerty.key('a').unwrap().pos);
+        assert_eq!(qwerty.char_at(shifted), Some('A'));
+        //the same place in another layout
+        assert_eq!(layout("colemak").char_at(qwerty.key('e').unwrap()), Some('f'));
+        assert_eq!(qwerty.key('ł'), None);
+        assert!(find(&layouts, "unknown").is_none());
+    }
+
+    #[test]
+    fn user_layouts_are_read_from_folder() {
+        let dir = env::temp_dir().join(format!("typing_game_layouts_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let rows = "rows = [\"1234567890\", \"qwertyuiop\", \"asdfghjkl\", \" zxcvbnm\"]";
+        fs::write(dir.join("mine.toml"), format!("name = \"Mine\"\n{}", rows)).unwrap();
+        fs::write(dir.join("broken.toml"), "name = \"Broken\"\nrows = [\"abc\"]").unwrap();
+        fs::write(dir.join("notes.txt"), "not a layout").unwrap();
+
+        let layouts = all(dir.to_str().unwrap());
+        assert_eq!(layouts.len(), 7);
+        let mine = find(&layouts, "mine").unwrap();
+        assert_eq!(mine.name, "Mine");
+        //shift rows are upper case of rows
+        assert_eq!(mine.key('Q').unwrap(), Key { pos: KeyPos { row: 1, col: 0 }, shift: true });
+        assert!(read_layout(&dir.join("broken.toml")).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
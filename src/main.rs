@@ -1,100 +1,275 @@
 use crossterm::event::KeyEvent;
 use input::InputSignal;
 use std::{
-    io::Result, sync::mpsc, thread, time::{Duration, SystemTime}
+    env, fs,
+    path::Path,
+    io::Result,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, SystemTime},
 };
 
 use ratatui::Frame;
 
-use game::Game;
 use model::Model;
+use typing_engine::game::{self, Game};
 
-use crate::component::{Component, GameComp, MenuComp, StatComp, WindowType};
+use crate::component::{
+    Component, GameComp, GameConfigComp, MenuComp, ProfileComp, ProgressComp, SettingsComp, StatComp,
+    WindowType,
+};
+use crate::config_manager::{GameConfPreset, Settings};
+use crate::finger_stats::FingerStats;
+use crate::game::TextSource;
+use crate::history::{History, RunRecord};
+use crate::keymap::Action;
+use crate::language::Languages;
+use crate::profile::Profile;
 // use crate::Message::GameStopped;
 
 mod component;
-mod game;
+mod export;
+mod finger_stats;
+#[cfg(test)]
+mod headless;
+mod history;
+mod import;
 mod input;
+mod keymap;
+mod language;
+mod layout;
 mod model;
+mod profile;
+mod progress;
 mod tui;
 mod config_manager;
+#[allow(clippy::large_enum_variant)]
 enum OutsideMessage {
     Message(Message),
-    InputSignal(Option<InputSignal>),
+    InputSignal(InputSignal),
 }
+///how often input thread checks if it should stop
+const INPUT_POLL_PERIOD: Duration = Duration::from_millis(100);
 fn main() -> Result<()> {
-    tui::install_panic_hook();//something to fix terminal if closed with Ctrl+C
-    let mut terminal = tui::init_terminal()?;
-    // tui::restore_terminal_new(&mut terminal);
-    // exit(0);
-    let gen_text = config_manager::read_markov_text_file();
-    let mut game_model = Model {
-        active_window: WindowType::Menu(MenuComp::new()),
-        running_state: model::RunningState::Running,
-        gen_text
+    let root = Path::new(profile::PROFILES_DIR);
+    profile::adopt_legacy_settings(root, config_manager::SETTINGS_PATH);
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    //profile can be chosen before start, otherwise it is chosen in profile window
+    let chosen_profile = match args.as_slice() {
+        [option, name, ..] if option == "--profile" => {
+            if !profile::list(root).contains(name) {
+                eprintln!("profile {} doesn't exist", name);
+                process::exit(2);
+            }
+            let chosen = Profile::new(root, name);
+            args.drain(..2);
+            Some(chosen)
+        }
+        _ => None,
     };
-    let tick_delay = Duration::from_millis(100);//delay between every game logic calculation and render
-    let (tx_input, rx) = mpsc::channel();//create chanel to get input signals
-    let tx_tick = tx_input.clone();//crete sender for tick signals
-    let _tick_thread = Box::new(thread::spawn(move || loop {
-        thread::sleep(tick_delay);
-        _ = tx_tick.send(OutsideMessage::Message(Message::Tick));
-    }));
-    let _input_thread = thread::spawn(move || loop {
-        _ = tx_input.send(OutsideMessage::InputSignal(input::get_input_process_input()));
+    let profile = chosen_profile.clone().unwrap_or_else(|| profile::last_used(root));
+    if !args.is_empty() {
+        return run_command(&profile, &args);
+    }
+    tui::install_panic_hook();//something to fix terminal if program panics
+    let mut terminal = tui::init_terminal()?;
+    let (settings, problem) = profile.load_settings();
+    let mut game_model = Model::new(profile, settings);
+    if let WindowType::Menu(menu) = &mut game_model.active_window {
+        menu.status = model::notice([problem, menu.status.take()]);
+    }
+    if chosen_profile.is_none() && profile::list(root).len() > 1 {
+        let keymap = game_model.settings.input.keymap.clone();
+        game_model.active_window = WindowType::Profiles(ProfileComp::new(&game_model.profile, keymap));
+    }
+
+    let running = Arc::new(AtomicBool::new(true));//background threads stop when it is false
+    let terminate = Arc::new(AtomicBool::new(false));//set by termination signals
+    signal_hook::flag::register(signal_hook::consts::SIGTERM, terminate.clone())?;
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, terminate.clone())?;
+
+    let (tx, rx) = mpsc::channel();//create chanel to get input signals
+    let input_running = running.clone();
+    let input_thread = thread::spawn(move || {
+        let mut reader = input::InputReader::default();
+        while input_running.load(Ordering::Relaxed) {
+            if terminate.load(Ordering::Relaxed) {
+                _ = tx.send(OutsideMessage::Message(Message::Quit));
+                break;
+            }
+            //waits for input only for a while so the thread can notice that it should stop
+            if let Some(sig) = reader.next(INPUT_POLL_PERIOD) {
+                _ = tx.send(OutsideMessage::InputSignal(sig));
+            }
+        }
     });
 
-    'outer: while game_model.running_state == model::RunningState::Running {
-        terminal.draw(|f| view(&mut game_model, f))?;
-        // tui::restore_terminal_new(&mut terminal);
-        // exit(0);
-        // let input = input::get_input_process_input();
-        let mut current_msg = match rx.recv() {
-            //? Not Safe
-            Ok(om) => match om {
-                OutsideMessage::Message(msg) => Some(msg),
-                OutsideMessage::InputSignal(sig) => match sig {
-                    Some(s) => match s {
-                        InputSignal::Key(key) => Some(Message::KeyInput(key)),
-                        InputSignal::TerminateProgram => {
-                            let _ = tui::restore_terminal(&mut terminal);
-                            panic!("TODO: better program termination")
-                        }
-                    },
-                    None => None,
-                },
+    let mut input_error = None;
+    terminal.draw(|f| view(&mut game_model, f))?;
+    while game_model.running_state == model::RunningState::Running {
+        //sleeps until input comes or active window needs to be updated
+        let received = match game_model.active_window.next_tick() {
+            Some(at) => rx.recv_timeout(at.duration_since(SystemTime::now()).unwrap_or_default()),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let msg = match received {
+            Ok(OutsideMessage::Message(msg)) => Some(msg),
+            Ok(OutsideMessage::InputSignal(sig)) => match sig {
+                InputSignal::Key(key) => Some(Message::KeyInput(key)),
+                InputSignal::Resize => None, //new size is picked up on draw
+                InputSignal::FocusLost => Some(Message::FocusLost),
+                InputSignal::Paste(text) => Some(Message::Paste(text)),
+                InputSignal::Error(e) => {
+                    input_error = Some(e);
+                    Some(Message::Quit)
+                }
             },
-            Err(_) => {
-                // println!("{:?}", e);
-                // tick_thread.join();
-                // input_thread.join();//IDl why this didn't work but it is probably not needed
-                None
-            }
+            Err(RecvTimeoutError::Timeout) => Some(Message::Tick),
+            Err(RecvTimeoutError::Disconnected) => Some(Message::Quit), //input thread is gone, nothing to wait for
         };
+        if let Some(msg) = msg {
+            dispatch(&mut game_model, msg);
+        }
+        if game_model.running_state == model::RunningState::Running {
+            terminal.draw(|f| view(&mut game_model, f))?;
+        }
+    }
 
-        while current_msg.is_some() {
-            current_msg = update(&mut game_model, current_msg.unwrap());
-            if let Some(Message::Quit) = current_msg {
-                break 'outer
+    running.store(false, Ordering::Relaxed);
+    let _ = input_thread.join();
+    let save_result = game_model.history.flush();
+    tui::restore_terminal(&mut terminal)?;
+    if let Some(e) = input_error {
+        eprintln!("problem occurred during input processing\n{}", e);
+    }
+    if let Err(e) = save_result {
+        eprintln!("failed to save history to {}: {}", game_model.history.path, e);
+    }
+    Ok(())
+}
+/// settings of the profile for commands, problem with settings file is printed
+fn command_settings(profile: &Profile) -> Settings {
+    let (settings, problem) = profile.load_settings();
+    if let Some(problem) = problem {
+        eprintln!("{}", problem);
+    }
+    settings
+}
+/// runs command given in arguments for the profile instead of the game
+fn run_command(profile: &Profile, args: &[String]) -> Result<()> {
+    match args {
+        [command, path] if command == "export" => {
+            let settings = command_settings(profile);
+            let history = History::new(&settings.paths.history);
+            if let Err(e) = export::write(path, history.records()) {
+                eprintln!("failed to export history to {}: {}", path, e);
+                process::exit(1);
             }
+            println!("exported {} runs to {}", history.records().len(), path);
+        }
+        [command, path, format @ ..] if command == "import" && format.len() <= 1 => {
+            let format = match format.first() {
+                Some(name) => match import::ImportFormat::from_name(name) {
+                    Some(format) => Some(format),
+                    None => {
+                        eprintln!("unknown format {}, use monkeytype, typeracer, csv or json", name);
+                        process::exit(2);
+                    }
+                },
+                None => None,
+            };
+            let settings = command_settings(profile);
+            let mut history = History::new(&settings.paths.history);
+            let imported = import::read(path, format).and_then(|import| {
+                let count = import.records.len();
+                history.import(import.records).map(|added| (added, count - added, import.skipped))
+            });
+            match imported {
+                Ok((added, known, skipped)) => println!(
+                    "imported {} runs from {} ({} already in history, {} lines skipped)",
+                    added, path, known, skipped
+                ),
+                Err(e) => {
+                    eprintln!("failed to import {}: {}", path, e);
+                    process::exit(1);
+                }
+            }
+        }
+        _ => {
+            eprintln!("usage: typing_game [--profile <name>] [export <file.csv|file.json> | import <file> [monkeytype|typeracer|csv|json]]");
+            eprintln!("  --profile  starts with the profile or runs command for it, last used profile by default");
+            eprintln!("  export     writes every run from history to file");
+            eprintln!("  import     adds runs from other programs or from exported file to history");
+            process::exit(2);
         }
     }
-    let _ = tui::restore_terminal(&mut terminal);
     Ok(())
 }
 // #[allow(dead_code)]
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug)]
 enum Message {
     KeyInput(KeyEvent),
+    ///key bound to action in key map
+    Action(Action),
+    ///text pasted into terminal
+    Paste(String),
+    ///terminal window is not active anymore
+    FocusLost,
     Tick,
     StartGame(game::GameConf),
+    ///start game with the same text again
+    RestartGame(Game),
     StopGame,
     GameStopped(Option<Game>),
     GoToWindow(WindowType),
+    OpenMenu,
+    OpenGameConfig,
+    OpenSettings,
+    OpenProgress,
+    ///write run to exports folder
+    ExportRun(RunRecord),
+    OpenProfiles,
+    ///use profile with the name
+    SwitchProfile(String),
+    ///rename profile from the first name to the second
+    RenameProfile(String, String),
+    SettingsChanged(Settings),
+    PresetsChanged(Vec<GameConfPreset>),
     Quit,
 }
 
+/// updates model with message and all messages produced by it
+fn dispatch(model: &mut Model, msg: Message) {
+    let mut current_msg = Some(msg);
+    while let Some(msg) = current_msg {
+        current_msg = update(model, msg);
+    }
+}
+
 fn update(model: &mut Model, msg: Message) -> Option<Message> {
+    let msg = match msg {
+        Message::KeyInput(key) => {
+            //layout is emulated only for typed text, menus keep system layout
+            let key = match (&model.active_window, model.emulated_layout()) {
+                (WindowType::Game(_), Some(emulated)) => input::emulate_layout(key, model.layout(), emulated),
+                _ => key,
+            };
+            let context = model.active_window.key_context();
+            match model.settings.input.keymap.resolve(context, &key) {
+                Some(Action::Quit) => return process_answer(model, Message::Quit),
+                Some(action) => Message::Action(action),
+                None => Message::KeyInput(key),
+            }
+        }
+        msg => msg,
+    };
     let answer = model.active_window.handle_message(msg);
     process_answer(model, answer)
 }
@@ -102,11 +277,33 @@ fn update(model: &mut Model, msg: Message) -> Option<Message> {
 fn process_answer(model: &mut Model, answer: Message) -> Option<Message> {
     match answer {
         Message::StartGame(conf) => {
-            model.active_window = WindowType::Game(GameComp::new(
-                Game::new(1000, conf, Some(model.gen_text.clone())),
-                SystemTime::now(),
-                Duration::from_secs(1), //TODO: make it configurable with config file
-            ));
+            let game = match &conf.text_source {
+                TextSource::Generated => Game::generated(1000, conf.clone(), model.languages.chain(&conf.language)),
+                TextSource::File(path) => match fs::read_to_string(path) {
+                    Ok(text) => Game::from_text(conf.clone(), &text),
+                    Err(e) => {
+                        let mut comp = GameConfigComp::new(
+                            conf.clone(),
+                            model.game_confs.presets.clone(),
+                            model.history.personal_bests(),
+                            model.languages.names(),
+                            model.settings.input.keymap.clone(),
+                        );
+                        comp.status = Some(format!("can't read {}: {}", path, e));
+                        return Some(Message::GoToWindow(WindowType::GameConfigMenu(comp)));
+                    }
+                },
+            };
+            model.game_confs.last = Some(conf);
+            let saved = config_manager::save_game_conf_store(&model.settings.paths.game_confs, &model.game_confs);
+            start_game(model, game);
+            if let (Err(e), WindowType::Game(comp)) = (saved, &mut model.active_window) {
+                comp.status = Some(format!("configuration is not remembered: {}", e));
+            }
+            None
+        }
+        Message::RestartGame(game) => {
+            start_game(model, game.restart());
             None
         }
         Message::StopGame => Some(match &mut model.active_window {
@@ -117,29 +314,244 @@ fn process_answer(model: &mut Model, answer: Message) -> Option<Message> {
             _ => Message::GameStopped(None),
         }),
         Message::Quit => {
-            Some(Message::Quit)
+            model.running_state = model::RunningState::Done;
+            None
         }
         Message::GameStopped(game) => match game {
-            Some(game) => {
+            Some(mut game) => {
                 if game.is_complete() {
+                    if model.settings.sound.bell_on_finish {
+                        tui::bell();
+                    }
+                    let statistics = game.get_statistics();
+                    let record = RunRecord::new(&game, &statistics);
+                    let personal_best = model.history.compare_with_best(&record);
+                    model.history.add(record);
+                    let fingers = FingerStats::new(&statistics.keystrokes, model.typing_layout());
                     Some(Message::GoToWindow(WindowType::Statistics(StatComp {
                         game,
-                        statistics: None,
+                        statistics: Some(statistics),
+                        personal_best,
+                        status: None,
+                        fingers,
+                        keymap: model.settings.input.keymap.clone(),
                     })))
                 } else {
-                    Some(Message::GoToWindow(WindowType::Menu(MenuComp::new())))
+                    Some(Message::OpenMenu)
                 }
             }
-            None => Some(Message::GoToWindow(WindowType::Menu(MenuComp::new()))),
+            None => Some(Message::OpenMenu),
         },
         Message::GoToWindow(window) => {
             model.active_window = window;
             None
         }
+        Message::OpenMenu => Some(Message::GoToWindow(WindowType::Menu(MenuComp::new(
+            model.settings.input.keymap.clone(),
+        )))),
+        Message::OpenGameConfig => Some(Message::GoToWindow(WindowType::GameConfigMenu(
+            GameConfigComp::new(
+                model
+                    .game_confs
+                    .last
+                    .clone()
+                    .unwrap_or(model.settings.gameplay.game_conf.clone()),
+                model.game_confs.presets.clone(),
+                model.history.personal_bests(),
+                model.languages.names(),
+                model.settings.input.keymap.clone(),
+            ),
+        ))),
+        Message::OpenSettings => Some(Message::GoToWindow(WindowType::Settings(
+            SettingsComp::new(model.settings.clone(), model.profile.settings_path(), model.layout_names()),
+        ))),
+        Message::OpenProgress => Some(Message::GoToWindow(WindowType::Progress(ProgressComp::new(
+            model.history.records().to_vec(),
+            model.settings.input.keymap.clone(),
+        )))),
+        Message::ExportRun(record) => {
+            let status = match export::write_run(&model.settings.paths.exports, &record) {
+                Ok(paths) => format!("Exported to {}", paths.join(", ")),
+                Err(e) => format!("Export failed: {}", e),
+            };
+            if let WindowType::Statistics(comp) = &mut model.active_window {
+                comp.status = Some(status);
+            }
+            None
+        }
+        Message::OpenProfiles => Some(Message::GoToWindow(WindowType::Profiles(ProfileComp::new(
+            &model.profile,
+            model.settings.input.keymap.clone(),
+        )))),
+        Message::SwitchProfile(name) => {
+            let status = model.load_profile(Profile::new(model.profile.root.clone(), &name));
+            let mut menu = MenuComp::new(model.settings.input.keymap.clone());
+            menu.status = status;
+            Some(Message::GoToWindow(WindowType::Menu(menu)))
+        }
+        Message::RenameProfile(from, to) => {
+            let in_use = from == model.profile.name;
+            if in_use {
+                let _ = model.history.flush(); //history file is moved with profile folder
+            }
+            let result = profile::rename(&model.profile.root, &from, &to);
+            if let (Ok(renamed), true) = (&result, in_use) {
+                let _ = model.load_profile(renamed.clone());
+            }
+            if let WindowType::Profiles(comp) = &mut model.active_window {
+                comp.current = model.profile.name.clone();
+                match result {
+                    Ok(renamed) => {
+                        comp.refresh(&renamed.name);
+                        comp.status = Some(format!("Profile {} is renamed to {}", from, renamed.name));
+                    }
+                    Err(e) => comp.status = Some(e),
+                }
+            }
+            None
+        }
+        Message::SettingsChanged(settings) => {
+            if settings.paths.languages != model.settings.paths.languages {
+                model.languages = Languages::new(&settings.paths.languages);
+            }
+            if settings.paths.layouts != model.settings.paths.layouts {
+                model.layouts = layout::all(&settings.paths.layouts);
+            }
+            if settings.paths.game_confs != model.settings.paths.game_confs {
+                let (game_confs, problem) = config_manager::read_game_conf_store(&settings.paths.game_confs);
+                model.game_confs = game_confs;
+                if let (Some(problem), WindowType::Settings(comp)) = (problem, &mut model.active_window) {
+                    comp.status = Some(problem);
+                }
+            }
+            if settings.paths.history != model.settings.paths.history {
+                let _ = model.history.flush();
+                model.history = History::new(&settings.paths.history);
+            }
+            model.settings = settings;
+            None
+        }
+        Message::PresetsChanged(presets) => {
+            model.game_confs.presets = presets;
+            let saved = config_manager::save_game_conf_store(&model.settings.paths.game_confs, &model.game_confs);
+            if let (Err(e), WindowType::GameConfigMenu(comp)) = (saved, &mut model.active_window) {
+                comp.status = Some(format!("Presets are not saved: {}", e));
+            }
+            None
+        }
         _ => None,
     }
 }
 
+fn start_game(model: &mut Model, game: Game) {
+    model.active_window = WindowType::Game(GameComp::new(
+        game,
+        model.settings.clone(),
+        model.keyboard_layout().cloned(),
+    ));
+}
+
 fn view(model: &mut Model, f: &mut Frame) {
     model.active_window.view(f);
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::{GameConf, Limit};
+    use crate::headless::{HeadlessRunner, TestDir};
+    use crate::model::RunningState;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn menu_to_stats_flow() {
+        let dir = TestDir::new("menu_to_stats_flow");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf = GameConf {
+            limit: Limit::None,
+            text_source: TextSource::File(dir.file("text.txt", "hello  world\n")),
+            ..GameConf::new()
+        };
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 100, 30);
+
+        runner.key(KeyCode::Enter);
+        assert!(matches!(runner.model.active_window, WindowType::GameConfigMenu(_)));
+        assert!(runner.screen().contains("Game configuration"));
+
+        runner.key(KeyCode::Enter);
+        assert!(matches!(runner.model.active_window, WindowType::Game(_)));
+        runner.type_text("hello wor").tick(3);
+        assert!(runner.screen().contains("hello world"));
+
+        runner.type_text("ld");
+        assert!(matches!(runner.model.active_window, WindowType::Statistics(_)));
+        assert!(runner.screen().contains("Statistics"));
+        let history = fs::read_to_string(&runner.model.settings.paths.history).unwrap();
+        assert_eq!(history.lines().count(), 1);
+        //test script types faster than human
+        assert!(runner.screen().contains("Run is not counted: superhuman speed"));
+        assert!(fs::read_to_string(&runner.model.settings.paths.game_confs).is_ok());
+
+        //same text again from statistics window
+        runner.key(KeyCode::Char('r'));
+        match &runner.model.active_window {
+            WindowType::Game(comp) => assert_eq!(comp.game.correct_text.concat(), "hello world"),
+            _ => panic!("game should be restarted"),
+        }
+
+        runner.key(KeyCode::Esc).key(KeyCode::Esc);
+        assert!(matches!(runner.model.active_window, WindowType::Menu(_)));
+        runner.key(KeyCode::Up).key(KeyCode::Enter);
+        assert_eq!(runner.model.running_state, RunningState::Done);
+    }
+
+    #[test]
+    fn generated_text_game_flow() {
+        let dir = TestDir::new("generated_text_game_flow");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf.limit = Limit::WordCount(2);
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        runner.key(KeyCode::Enter).key(KeyCode::Enter);
+        assert!(runner.screen().contains("aa aa"));
+        runner.type_text("aa ab ");
+        match &runner.model.active_window {
+            WindowType::Statistics(comp) => assert_eq!(comp.game.get_correct_words_count(), 1),
+            _ => panic!("game should be finished"),
+        }
+    }
+
+    #[test]
+    fn game_configuration_write_errors_are_shown() {
+        let dir = TestDir::new("game_configuration_write_errors_are_shown");
+        let mut settings = dir.settings();
+        settings.paths.game_confs = dir.path.join("missing").join("game_confs.toml").to_string_lossy().to_string();
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 120, 24);
+        runner.key(KeyCode::Enter).key(KeyCode::Enter);
+        assert!(runner.screen().contains("configuration is not remembered:"));
+        runner.key(KeyCode::Esc).key(KeyCode::Enter);
+        runner.key(KeyCode::Up).key(KeyCode::Char('s')).type_text("sprint").key(KeyCode::Enter);
+        assert!(runner.screen().contains("Presets are not saved:"));
+    }
+
+    #[test]
+    fn ticks_are_scheduled_only_for_running_game() {
+        let dir = TestDir::new("ticks_are_scheduled_only_for_running_game");
+        let mut settings = dir.settings();
+        settings.gameplay.game_conf.limit = Limit::Time(Duration::from_secs(1));
+        let mut runner = HeadlessRunner::new(Model::new(dir.profile(), settings), 80, 24);
+        assert!(runner.model.active_window.next_tick().is_none());
+        runner.key(KeyCode::Enter).key(KeyCode::Enter);
+        let next = runner.model.active_window.next_tick().unwrap();
+        assert!(next <= SystemTime::now() + Duration::from_secs(1));
+        runner.key_with(KeyCode::Char('p'), KeyModifiers::CONTROL);
+        assert!(runner.model.active_window.next_tick().is_none());
+        runner.key_with(KeyCode::Char('p'), KeyModifiers::CONTROL);
+
+        //time limit ends the game on scheduled tick without extra waiting
+        let started = SystemTime::now();
+        while runner.wait_tick() {
+            assert!(started.elapsed().unwrap() < Duration::from_secs(2));
+        }
+        assert!(matches!(runner.model.active_window, WindowType::Statistics(_)));
+    }
+}
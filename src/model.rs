@@ -1,10 +1,110 @@
-use crate::component;
+use crate::component::{self, MenuComp, WindowType};
+use crate::config_manager::{self, GameConfStore, Settings};
+use crate::history::History;
+use crate::language::{self, Languages};
+use crate::layout::{self, KeyboardLayout};
+use crate::profile::{self, Profile};
 
 pub struct Model {
     pub active_window: component::WindowType,
     pub running_state: RunningState,
-    ///text used to generate markov chain
-    pub gen_text: String
+    ///language packs for generated text
+    pub languages: Languages,
+    ///built-in and user keyboard layouts
+    pub layouts: Vec<KeyboardLayout>,
+    ///user preferences read from settings file
+    pub settings: Settings,
+    ///last used game configuration and presets
+    pub game_confs: GameConfStore,
+    ///results of finished games
+    pub history: History,
+    ///profile settings and results belong to
+    pub profile: Profile,
+}
+impl Model {
+    /// creates model that starts in menu, texts and stored data are read from paths in settings
+    pub fn new(profile: Profile, settings: Settings) -> Model {
+        let mut menu = MenuComp::new(settings.input.keymap.clone());
+        let (game_confs, problem) = config_manager::read_game_conf_store(&settings.paths.game_confs);
+        menu.status = notice([import_legacy_text(&settings), problem]);
+        Model {
+            active_window: WindowType::Menu(menu),
+            running_state: RunningState::Running,
+            languages: Languages::new(&settings.paths.languages),
+            layouts: layout::all(&settings.paths.layouts),
+            game_confs,
+            history: History::new(&settings.paths.history),
+            settings,
+            profile,
+        }
+    }
+
+    /// replaces settings and stored data with the ones of another profile,
+    /// returns what was imported and problems with files of the profile
+    pub fn load_profile(&mut self, profile: Profile) -> Option<String> {
+        let _ = self.history.flush();
+        let (settings, settings_problem) = profile.load_settings();
+        let imported = import_legacy_text(&settings);
+        if settings.paths.languages != self.settings.paths.languages || imported.is_some() {
+            self.languages = Languages::new(&settings.paths.languages);
+        }
+        self.layouts = layout::all(&settings.paths.layouts);
+        let (game_confs, game_confs_problem) = config_manager::read_game_conf_store(&settings.paths.game_confs);
+        self.game_confs = game_confs;
+        self.history = History::new(&settings.paths.history);
+        self.settings = settings;
+        let _ = profile::set_last_used(&profile);
+        self.profile = profile;
+        notice([imported, settings_problem, game_confs_problem])
+    }
+
+    /// keyboard layout chosen in settings, default layout if there is no such layout
+    pub fn layout(&self) -> &KeyboardLayout {
+        layout::find(&self.layouts, &self.settings.input.layout)
+            .or(layout::find(&self.layouts, layout::DEFAULT_LAYOUT))
+            .unwrap_or(&self.layouts[0])
+    }
+
+    /// layout typed keys are turned into, `None` if emulation is off or there is no such layout
+    pub fn emulated_layout(&self) -> Option<&KeyboardLayout> {
+        let id = self.settings.input.emulated_layout.as_ref()?;
+        layout::find(&self.layouts, id)
+    }
+
+    /// layout chars are typed in, emulated layout while it is on
+    pub fn typing_layout(&self) -> &KeyboardLayout {
+        self.emulated_layout().unwrap_or(self.layout())
+    }
+
+    /// layout of on-screen keyboard, `None` if keyboard is hidden
+    pub fn keyboard_layout(&self) -> Option<&KeyboardLayout> {
+        if self.settings.hud.show_keyboard || self.emulated_layout().is_some() {
+            Some(self.typing_layout())
+        } else {
+            None
+        }
+    }
+
+    /// ids and names of all keyboard layouts
+    pub fn layout_names(&self) -> Vec<(String, String)> {
+        self.layouts.iter().map(|l| (l.id.clone(), l.name.clone())).collect()
+    }
+}
+
+/// joins messages for the player into lines, `None` if there is nothing to say
+pub fn notice<const N: usize>(messages: [Option<String>; N]) -> Option<String> {
+    let lines: Vec<String> = messages.into_iter().flatten().collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join("\n"))
+    }
+}
+
+/// imports text for generation of older versions as user language pack, returns what was done
+fn import_legacy_text(settings: &Settings) -> Option<String> {
+    let path = settings.paths.markov_text.as_deref().unwrap_or(language::LEGACY_TEXT);
+    language::import_legacy_text(path, &settings.paths.languages)
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Default)]
@@ -14,3 +114,53 @@ pub enum RunningState {
     Running,
     Done,
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::headless::TestDir;
+    use std::fs;
+
+    #[test]
+    fn unknown_layouts_are_not_emulated() {
+        let dir = TestDir::new("unknown_layouts_are_not_emulated");
+        let mut settings = dir.settings();
+        settings.input.layout = "missing".to_string();
+        settings.input.emulated_layout = Some("missing".to_string());
+        let model = Model::new(dir.profile(), settings);
+        assert_eq!(model.layout().id, layout::DEFAULT_LAYOUT);
+        assert!(model.emulated_layout().is_none());
+        assert!(model.keyboard_layout().is_none());
+    }
+
+    #[test]
+    fn legacy_text_becomes_language_pack() {
+        let dir = TestDir::new("legacy_text_becomes_language_pack");
+        let mut settings = dir.settings();
+        let legacy: Settings = toml::from_str("[paths]\nmarkov_text = \"my text.txt\"\n").unwrap();
+        assert_eq!(legacy.paths.markov_text.as_deref(), Some("my text.txt"));
+        assert!(!toml::to_string(&legacy).unwrap().contains("markov_text"));
+        settings.paths.markov_text = Some(dir.file("my text.txt", "cc cc cc"));
+        let model = Model::new(dir.profile(), settings);
+        assert_eq!(model.languages.pack(language::IMPORTED_LANGUAGE).unwrap().name, "Custom text");
+        let mut runner = crate::headless::HeadlessRunner::new(model, 200, 20);
+        assert!(runner.screen().contains("my text.txt was imported as language Custom text"));
+    }
+
+    #[test]
+    fn broken_files_are_reported() {
+        let dir = TestDir::new("broken_files_are_reported");
+        let settings = dir.settings();
+        fs::write(&settings.paths.game_confs, "presets = [").unwrap();
+        fs::write(dir.profile().settings_path(), "[hud\n").unwrap();
+        let mut model = Model::new(dir.profile(), settings);
+        let status = match &model.active_window {
+            WindowType::Menu(menu) => menu.status.clone().unwrap(),
+            _ => panic!("menu is expected"),
+        };
+        assert!(status.contains("game_confs.toml is broken"));
+        let notice = model.load_profile(dir.profile()).unwrap();
+        assert!(notice.contains("settings.toml is broken"), "{}", notice);
+        assert_eq!(notice.lines().count(), 2);
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config_manager::{self, Settings, SETTINGS_PATH};
+
+/// folder with folder of every profile
+pub const PROFILES_DIR: &str = "profiles";
+/// profile created on first start
+const DEFAULT_PROFILE: &str = "default";
+/// file in profiles folder that remembers last used profile
+const PROFILES_FILE: &str = "profiles.toml";
+const MAX_NAME_LENGTH: usize = 32;
+
+/// user of the program, every profile keeps its settings and results in own folder
+#[derive(Debug, Clone, PartialEq)]
+pub struct Profile {
+    ///folder with all profiles
+    pub root: PathBuf,
+    pub name: String,
+}
+impl Profile {
+    pub fn new(root: impl Into<PathBuf>, name: &str) -> Profile {
+        Profile {
+            root: root.into(),
+            name: name.to_string(),
+        }
+    }
+
+    pub fn dir(&self) -> PathBuf {
+        self.root.join(&self.name)
+    }
+
+    pub fn settings_path(&self) -> String {
+        self.dir().join(SETTINGS_PATH).to_string_lossy().to_string()
+    }
+
+    /// settings of new profile, every file except language packs and keyboard layouts is kept in profile folder
+    pub fn default_settings(&self) -> Settings {
+        let mut settings = Settings::default();
+        let in_dir = |file: &str| self.dir().join(file).to_string_lossy().to_string();
+        settings.paths.history = in_dir(&settings.paths.history);
+        settings.paths.game_confs = in_dir(&settings.paths.game_confs);
+        settings.paths.exports = in_dir(&settings.paths.exports);
+        settings
+    }
+
+    /// reads settings of profile, missing file is created with default settings
+    pub fn read_settings(&self) -> Settings {
+        self.load_settings().0
+    }
+
+    /// reads settings of profile like `read_settings`, also returns the problem if settings file is broken
+    pub fn load_settings(&self) -> (Settings, Option<String>) {
+        if !Path::new(&self.settings_path()).exists() {
+            let _ = config_manager::save_settings(&self.settings_path(), &self.default_settings());
+        }
+        config_manager::read_settings(&self.settings_path())
+    }
+}
+
+/// data shared by all profiles
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProfilesConf {
+    ///name of the last used profile
+    last: Option<String>,
+}
+
+/// names of all profiles in alphabetical order
+pub fn list(root: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(root)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.path().is_dir())
+                .filter_map(|entry| entry.file_name().to_str().map(|name| name.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// profile used on start: the last used one, default profile is created if there are none
+pub fn last_used(root: &Path) -> Profile {
+    let names = list(root);
+    let conf: ProfilesConf = fs::read_to_string(root.join(PROFILES_FILE))
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default();
+    match conf.last.filter(|last| names.contains(last)).or(names.first().cloned()) {
+        Some(name) => Profile::new(root, &name),
+        None => {
+            let _ = create(root, DEFAULT_PROFILE);
+            Profile::new(root, DEFAULT_PROFILE)
+        }
+    }
+}
+
+/// remembers profile to start with it next time
+pub fn set_last_used(profile: &Profile) -> Result<(), String> {
+    let conf = ProfilesConf {
+        last: Some(profile.name.clone()),
+    };
+    let text = toml::to_string(&conf).map_err(|e| e.to_string())?;
+    fs::create_dir_all(&profile.root).map_err(|e| e.to_string())?;
+    fs::write(profile.root.join(PROFILES_FILE), text).map_err(|e| e.to_string())
+}
+
+/// settings file from before profiles becomes settings of default profile,
+/// paths in it still point to old files so results are not lost
+pub fn adopt_legacy_settings(root: &Path, legacy_path: &str) {
+    if !list(root).is_empty() || !Path::new(legacy_path).is_file() {
+        return;
+    }
+    let profile = Profile::new(root, DEFAULT_PROFILE);
+    if fs::create_dir_all(profile.dir()).is_ok() {
+        let _ = fs::copy(legacy_path, profile.settings_path());
+    }
+}
+
+/// checks if name can be used for new profile
+fn validate_name(root: &Path, name: &str) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("Profile name can't be empty".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(format!("Profile name can't be longer than {} letters", MAX_NAME_LENGTH));
+    }
+    //name is used as folder name
+    if !name.chars().all(|c| c.is_alphanumeric() || c == ' ' || c == '-' || c == '_') {
+        return Err("Profile name can contain only letters, digits, spaces, - and _".to_string());
+    }
+    if list(root).iter().any(|other| other == name) {
+        return Err(format!("Profile {} already exists", name));
+    }
+    Ok(())
+}
+
+pub fn create(root: &Path, name: &str) -> Result<Profile, String> {
+    validate_name(root, name)?;
+    let profile = Profile::new(root, name);
+    fs::create_dir_all(profile.dir()).map_err(|e| e.to_string())?;
+    config_manager::save_settings(&profile.settings_path(), &profile.default_settings())?;
+    Ok(profile)
+}
+
+/// renames profile folder, paths in settings that point into the folder are changed too
+pub fn rename(root: &Path, from: &str, to: &str) -> Result<Profile, String> {
+    validate_name(root, to)?;
+    let old = Profile::new(root, from);
+    let new = Profile::new(root, to);
+    fs::rename(old.dir(), new.dir()).map_err(|e| e.to_string())?;
+    let mut settings = new.read_settings();
+    for path in [
+        &mut settings.paths.languages,
+        &mut settings.paths.layouts,
+        &mut settings.paths.history,
+        &mut settings.paths.game_confs,
+        &mut settings.paths.exports,
+    ] {
+        if let Ok(inside) = Path::new(path.as_str()).strip_prefix(old.dir()) {
+            *path = new.dir().join(inside).to_string_lossy().to_string();
+        }
+    }
+    config_manager::save_settings(&new.settings_path(), &settings)?;
+    Ok(new)
+}
+
+/// removes profile folder with all results in it
+pub fn delete(root: &Path, name: &str) -> Result<(), String> {
+    fs::remove_dir_all(Profile::new(root, name).dir()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn profile_flows() {
+        let root = env::temp_dir().join(format!("typing_game_profiles_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let default = last_used(&root);
+        assert_eq!(default.name, DEFAULT_PROFILE);
+        assert!(default.read_settings().paths.history.starts_with(default.dir().to_str().unwrap()));
+
+        let anna = create(&root, "anna").unwrap();
+        assert!(create(&root, "anna").is_err());
+        assert!(create(&root, "../up").is_err());
+        assert!(create(&root, " ").is_err());
+        assert_eq!(list(&root), vec!["anna", "default"]);
+        //first profile in list is used until other profile is chosen
+        assert_eq!(last_used(&root), anna);
+        set_last_used(&default).unwrap();
+        assert_eq!(last_used(&root), default);
+
+        fs::write(anna.read_settings().paths.history, "results").unwrap();
+        let renamed = rename(&root, "anna", "Anna K").unwrap();
+        let settings = renamed.read_settings();
+        assert!(settings.paths.history.starts_with(renamed.dir().to_str().unwrap()));
+        assert_eq!(fs::read_to_string(settings.paths.history).unwrap(), "results");
+        assert_eq!(settings.paths.languages, Settings::default().paths.languages);
+        assert!(rename(&root, "Anna K", "default").is_err());
+
+        delete(&root, "Anna K").unwrap();
+        assert_eq!(list(&root), vec!["default"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn legacy_settings_become_default_profile() {
+        let root = env::temp_dir().join(format!("typing_game_legacy_profiles_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let legacy = env::temp_dir().join(format!("typing_game_legacy_settings_{}.toml", std::process::id()));
+        let mut settings = Settings::default();
+        settings.sound.bell_on_finish = true;
+        config_manager::save_settings(legacy.to_str().unwrap(), &settings).unwrap();
+
+        adopt_legacy_settings(&root, legacy.to_str().unwrap());
+        let read = last_used(&root).read_settings();
+        assert!(read.sound.bell_on_finish);
+        //old history file keeps being used
+        assert_eq!(read.paths.history, Settings::default().paths.history);
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_file(&legacy);
+    }
+}
@@ -0,0 +1,177 @@
+use crate::history::{Origin, RunRecord};
+
+const SECONDS_IN_DAY: u64 = 24 * 60 * 60;
+
+/// length of one point on progress charts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Period {
+    Day,
+    Week,
+}
+impl Period {
+    /// first day of period that contains the day, days are counted from unix epoch
+    pub fn start(&self, day: u64) -> u64 {
+        match self {
+            Period::Day => day,
+            //unix epoch is thursday, weeks start on monday
+            Period::Week => day.saturating_sub((day + 3) % 7),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Period::Day => "day",
+            Period::Week => "week",
+        }
+    }
+}
+
+/// averaged results of runs finished during one period
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodSummary {
+    ///first day of period
+    pub day: u64,
+    pub runs: u32,
+    pub wpm: f64,
+    pub accuracy: f64,
+}
+
+/// results of all runs together
+#[derive(Debug, Clone, PartialEq)]
+pub struct Totals {
+    pub runs: usize,
+    pub time_secs: f64,
+    pub words: u32,
+    ///day with the best average speed
+    pub best_day: Option<PeriodSummary>,
+    ///runs imported from other programs
+    pub imported: usize,
+}
+
+/// groups records by periods in time order, periods without runs are skipped
+pub fn summarize(records: &[RunRecord], period: Period) -> Vec<PeriodSummary> {
+    let mut records: Vec<&RunRecord> = records.iter().collect();
+    records.sort_by_key(|r| r.finished_at);
+    let mut summaries: Vec<PeriodSummary> = Vec::new();
+    for record in records {
+        let day = period.start(record.finished_at / SECONDS_IN_DAY);
+        match summaries.last_mut() {
+            Some(last) if last.day == day => {
+                //running average
+                let runs = last.runs as f64;
+                last.wpm = (last.wpm * runs + record.wpm) / (runs + 1.0);
+                last.accuracy = (last.accuracy * runs + record.accuracy) / (runs + 1.0);
+                last.runs += 1;
+            }
+            _ => summaries.push(PeriodSummary {
+                day,
+                runs: 1,
+                wpm: record.wpm,
+                accuracy: record.accuracy,
+            }),
+        }
+    }
+    summaries
+}
+
+/// average of every value with up to `window - 1` values before it
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    (0..values.len())
+        .map(|i| {
+            let from = (i + 1).saturating_sub(window.max(1));
+            let part = &values[from..=i];
+            part.iter().sum::<f64>() / part.len() as f64
+        })
+        .collect()
+}
+
+pub fn totals(records: &[RunRecord]) -> Totals {
+    Totals {
+        runs: records.len(),
+        time_secs: records.iter().map(|r| r.duration_secs).sum(),
+        words: records.iter().map(|r| r.total_words).sum(),
+        best_day: summarize(records, Period::Day)
+            .into_iter()
+            .max_by(|a, b| a.wpm.total_cmp(&b.wpm)),
+        imported: records.iter().filter(|r| r.origin != Origin::Local).count(),
+    }
+}
+
+/// date of the day counted from unix epoch, like "2024-05-31"
+pub fn date_label(day: u64) -> String {
+    //civil from days algorithm by Howard Hinnant
+    let z = day as i64 + 719468;
+    let era = z.div_euclid(146097);
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = doy - (153 * mp + 2) / 5 + 1;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 };
+    let y = yoe + era * 400 + if m <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", y, m, d)
+}
+
+/// day counted from unix epoch for the date, `None` for dates before epoch
+pub fn day_number(year: i64, month: i64, day: i64) -> Option<u64> {
+    //days from civil algorithm by Howard Hinnant
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    u64::try_from(era * 146097 + doe - 719468).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::game::GameConf;
+
+    fn record(day: u64, wpm: f64) -> RunRecord {
+        RunRecord {
+            finished_at: day * SECONDS_IN_DAY + 100,
+            game_conf: GameConf::new(),
+            duration_secs: 30.0,
+            wpm,
+            raw_wpm: wpm,
+            accuracy: 90.0,
+            correct_words: 10,
+            total_words: 12,
+            correct_strokes: 0,
+            wrong_strokes: 0,
+            cheat_flags: Vec::new(),
+            typed_text: String::new(),
+            origin: Origin::Local,
+        }
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date_label(0), "1970-01-01");
+        assert_eq!(date_label(19875), "2024-06-01");
+        assert_eq!(date_label(Period::Week.start(19875)), "2024-05-27");
+        assert_eq!(Period::Week.start(1), 0);
+        assert_eq!(day_number(2024, 6, 1), Some(19875));
+        assert_eq!(date_label(day_number(2000, 2, 29).unwrap()), "2000-02-29");
+        assert_eq!(day_number(1969, 12, 31), None);
+    }
+
+    #[test]
+    fn summaries() {
+        let records = vec![record(11, 50.0), record(3, 40.0), record(11, 70.0), record(13, 90.0)];
+        let days = summarize(&records, Period::Day);
+        assert_eq!(days.iter().map(|d| d.day).collect::<Vec<u64>>(), vec![3, 11, 13]);
+        assert_eq!(days[1].wpm, 60.0);
+        assert_eq!(days[1].runs, 2);
+        //days 11 and 13 are monday and wednesday of the same week
+        assert_eq!(summarize(&records, Period::Week).len(), 2);
+        let totals = totals(&records);
+        assert_eq!(totals.time_secs, 120.0);
+        assert_eq!(totals.words, 48);
+        assert_eq!(totals.best_day.unwrap().day, 13);
+        assert_eq!(totals.imported, 0);
+        assert_eq!(rolling_average(&[1.0, 3.0, 5.0, 7.0], 2), vec![1.0, 2.0, 4.0, 6.0]);
+    }
+}
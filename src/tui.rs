@@ -1,35 +1,66 @@
 use crossterm::{
     cursor::{MoveTo, SetCursorStyle},
+    event::{
+        DisableBracketedPaste, DisableFocusChange, EnableBracketedPaste, EnableFocusChange,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    style::Print,
+    terminal::{
+        disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+        LeaveAlternateScreen,
+    },
     ExecutableCommand,
 };
 use ratatui::prelude::*;
 use std::io::Result;
 use std::{io::{stdout,Stdout}, panic};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+///keyboard enhancement flags were pushed and should be popped on exit
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
 
 pub fn init_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
     execute!(
         stdout(),
         EnterAlternateScreen,
+        EnableBracketedPaste, //pasted text comes as one event instead of key presses
+        EnableFocusChange,
         SetCursorStyle::DefaultUserShape,
         MoveTo(3, 3)
     )?;
+    if supports_keyboard_enhancement().unwrap_or(false) {
+        //tells apart held down keys from pressed ones
+        execute!(stdout(), PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES))?;
+        KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+    }
     let terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
     
     Ok(terminal)
 }
 
 pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+        execute!(terminal.backend_mut(), PopKeyboardEnhancementFlags)?;
+    }
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    execute!(terminal.backend_mut(), DisableBracketedPaste, DisableFocusChange, LeaveAlternateScreen)?;
     terminal.show_cursor()
 }
 
+/// rings terminal bell
+pub fn bell() {
+    let _ = stdout().execute(Print('\x07'));
+}
+
 pub fn install_panic_hook() {
     let original_hook = panic::take_hook();
     panic::set_hook(Box::new(move |panic_info| {
+        if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+            let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
+        }
+        let _ = execute!(stdout(), DisableBracketedPaste, DisableFocusChange);
         stdout().execute(LeaveAlternateScreen).unwrap();
         disable_raw_mode().unwrap();
         original_hook(panic_info);